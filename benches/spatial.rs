@@ -0,0 +1,101 @@
+//! Compares the spatial index against going through every unit, for as many units as the stress
+//! scene. Run with `cargo bench --bench spatial`.
+
+use std::{hint::black_box, time::Instant};
+
+use bevy::prelude::*;
+use rts_game_rs::{orders::UnitId, simulation::SimulationRng, units::spatial::SpatialIndex};
+
+const UNITS: u32 = 2000;
+const QUERIES: usize = 2000;
+const HALF_SIZE: f32 = 15.;
+const RADIUS: f32 = 2.;
+
+fn random_position(rng: &mut SimulationRng) -> Vec2 {
+    (Vec2::new(rng.next_f32(), rng.next_f32()) * 2. - Vec2::ONE) * HALF_SIZE
+}
+
+/// Runs `run` `QUERIES` times and prints the average time it took.
+fn measure(name: &str, mut run: impl FnMut(usize) -> usize) {
+    let start = Instant::now();
+    let mut found = 0;
+    for query in 0..QUERIES {
+        found += black_box(run(query));
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{name:<28} {:>9.2} µs per query, {:>6.1} found on average",
+        elapsed.as_secs_f64() * 1e6 / QUERIES as f64,
+        found as f64 / QUERIES as f64,
+    );
+}
+
+fn main() {
+    let mut rng = SimulationRng::from_seed(1);
+    let units: Vec<(Entity, Vec2)> = (0..UNITS)
+        .map(|id| (Entity::from_raw(id), random_position(&mut rng)))
+        .collect();
+    let centers: Vec<Vec2> = (0..QUERIES).map(|_| random_position(&mut rng)).collect();
+
+    let mut index = SpatialIndex::default();
+    let start = Instant::now();
+    for (entity, position) in &units {
+        index.insert(*entity, UnitId(entity.index()), *position);
+    }
+    println!(
+        "{:<28} {:>9.2} µs for {UNITS} units",
+        "build",
+        start.elapsed().as_secs_f64() * 1e6
+    );
+
+    // A tick of movement, most units staying in their cell
+    let start = Instant::now();
+    for (entity, position) in &units {
+        index.insert(
+            *entity,
+            UnitId(entity.index()),
+            *position + Vec2::new(0.03, 0.),
+        );
+    }
+    println!(
+        "{:<28} {:>9.2} µs for {UNITS} units",
+        "update",
+        start.elapsed().as_secs_f64() * 1e6
+    );
+
+    measure("radius, linear scan", |query| {
+        units
+            .iter()
+            .filter(|(_, position)| position.distance(centers[query]) <= RADIUS)
+            .count()
+    });
+    measure("radius, index", |query| {
+        index.within_radius(centers[query], RADIUS).count()
+    });
+
+    measure("rect, linear scan", |query| {
+        let area = Rect::from_center_half_size(centers[query], Vec2::splat(RADIUS));
+        units
+            .iter()
+            .filter(|(_, position)| area.contains(*position))
+            .count()
+    });
+    measure("rect, index", |query| {
+        let area = Rect::from_center_half_size(centers[query], Vec2::splat(RADIUS));
+        index.within_rect(area).count()
+    });
+
+    measure("8 nearest, linear scan", |query| {
+        let mut sorted: Vec<(f32, Entity)> = units
+            .iter()
+            .map(|(entity, position)| (position.distance_squared(centers[query]), *entity))
+            .collect();
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        sorted.truncate(8);
+        sorted.len()
+    });
+    measure("8 nearest, index", |query| {
+        index.nearest(centers[query], 8).len()
+    });
+}
@@ -0,0 +1,304 @@
+use bevy::prelude::*;
+
+use crate::{
+    buildings::Building,
+    config::{
+        ai::{AiConfig, BuildStep},
+        economy::EconomyConfig,
+    },
+    economy::{ExpansionSite, Gather, ResourceNode, UnitKind},
+    match_settings::{MatchSettings, SlotKind},
+    orders::{PlayerCommand, ScheduledCommands, UnitId},
+    players::{Owner, Player, PlayerId},
+    replay::ReplayPlayback,
+    simulation::{SimulationSet, SimulationTick},
+    units::{MoveTo, Unit, combat::Attack, hero::Hero},
+};
+
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AiController>().add_systems(
+            FixedUpdate,
+            (attach_controllers, think)
+                .chain()
+                .in_set(SimulationSet::Ai),
+        );
+    }
+}
+
+/// Drives a computer player through the [`AiConfig::build_order`]. It gives orders as
+/// [`PlayerCommand`]s scheduled like those of a human player, so that they are recorded in replays
+/// and played at the same tick on every machine of a lockstep match.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct AiController {
+    /// Index of the build step currently being worked on
+    pub step: usize,
+    /// Time left before the next decision, set from the difficulty's reaction time
+    pub reaction: Timer,
+}
+
+/// Gives every computer player a controller, both when the match starts and when it is loaded
+/// from a save made before the controller existed.
+fn attach_controllers(
+    mut commands: Commands,
+    players_query: Query<(Entity, &Player), (Added<Player>, Without<AiController>)>,
+    playback: Option<Res<ReplayPlayback>>,
+    ai_config: Res<AiConfig>,
+    match_settings: Res<MatchSettings>,
+) {
+    for (entity, player) in players_query.iter() {
+        // A recording already holds the orders this player gave, thinking again would give them
+        // twice. Scenarios leave computer players to think for themselves.
+        let replayed = playback.as_ref().is_some_and(|playback| {
+            playback
+                .0
+                .commands
+                .iter()
+                .any(|recorded| recorded.player == player.id)
+        });
+        if player.kind != SlotKind::Ai || replayed {
+            continue;
+        }
+        let difficulty = match_settings.slots[player.id.0 as usize].difficulty;
+        let reaction_time = ai_config.modifiers(difficulty).reaction_time;
+
+        commands.entity(entity).insert(AiController {
+            step: 0,
+            reaction: Timer::from_seconds(reaction_time, TimerMode::Repeating),
+        });
+    }
+}
+
+// Positions as far from the origin as each other are told apart by where they are rather than
+// by the order they came in, which depends on how entities are stored on each machine.
+fn closest_to(origin: Vec2, positions: impl Iterator<Item = Vec2>) -> Option<Vec2> {
+    positions.min_by(|a, b| {
+        a.distance_squared(origin)
+            .total_cmp(&b.distance_squared(origin))
+            .then(a.x.total_cmp(&b.x))
+            .then(a.y.total_cmp(&b.y))
+    })
+}
+
+/// What a computer player knows about the units it owns when it takes a decision.
+struct Army {
+    soldiers: u32,
+    heroes: u32,
+    gatherers: u32,
+    /// Soldiers without orders that are not holding an expansion site
+    idle_soldiers: Vec<UnitId>,
+}
+
+fn think(
+    mut controllers_query: Query<(&Player, &mut AiController)>,
+    players_query: Query<&Player>,
+    units_query: Query<
+        (
+            &UnitId,
+            &Owner,
+            &Transform,
+            Has<Hero>,
+            Has<MoveTo>,
+            Has<Attack>,
+            Has<Gather>,
+        ),
+        With<Unit>,
+    >,
+    sites_query: Query<(&ExpansionSite, &Transform)>,
+    nodes_query: Query<&Transform, With<ResourceNode>>,
+    buildings_query: Query<(&UnitId, &Owner, &Transform), With<Building>>,
+    mut scheduled: ResMut<ScheduledCommands>,
+    ai_config: Res<AiConfig>,
+    economy_config: Res<EconomyConfig>,
+    match_settings: Res<MatchSettings>,
+    tick: Res<SimulationTick>,
+    time: Res<Time>,
+) {
+    let claim_radius_squared = economy_config.expansion_claim_radius.powi(2);
+
+    for (player, mut controller) in controllers_query.iter_mut() {
+        if !controller.reaction.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let Some(step) = ai_config.build_order.get(controller.step) else {
+            continue;
+        };
+
+        // Played on the next tick, like the commands of a human player without lockstep
+        let mut order = |command: PlayerCommand| {
+            scheduled
+                .0
+                .entry(tick.0 + 1)
+                .or_default()
+                .push((player.id, command));
+        };
+
+        let mut army = Army {
+            soldiers: 0,
+            heroes: 0,
+            gatherers: 0,
+            idle_soldiers: Vec::new(),
+        };
+        for (id, owner, transform, is_hero, moving, attacking, gathering) in units_query.iter() {
+            if owner.0 != player.id {
+                continue;
+            }
+            if is_hero {
+                army.heroes += 1;
+                continue;
+            }
+            army.soldiers += 1;
+            if gathering {
+                army.gatherers += 1;
+                continue;
+            }
+
+            let holding_site = sites_query.iter().any(|(site, site_transform)| {
+                site.claimed_by == Some(player.id)
+                    && site_transform
+                        .translation
+                        .xz()
+                        .distance_squared(transform.translation.xz())
+                        < claim_radius_squared
+            });
+            if !moving && !attacking && !holding_site {
+                army.idle_soldiers.push(*id);
+            }
+        }
+
+        // Soldiers are picked for orders by id, the query walks them in a different order on every
+        // machine
+        army.idle_soldiers.sort();
+
+        let home = match_settings.map.start_positions[player.id.0 as usize];
+        let enemies: Vec<PlayerId> = players_query
+            .iter()
+            .filter(|other| other.team != player.team)
+            .map(|other| other.id)
+            .collect();
+
+        let done = match step {
+            BuildStep::Train { unit, count } => {
+                let owned = match unit {
+                    UnitKind::Soldier => army.soldiers,
+                    UnitKind::Hero => army.heroes,
+                };
+                if owned < *count && player.resources >= economy_config.cost(*unit) {
+                    order(PlayerCommand::Train { unit: *unit });
+                }
+                owned >= *count
+            }
+            BuildStep::Gather { workers } => {
+                let mine = closest_to(
+                    home,
+                    nodes_query
+                        .iter()
+                        .map(|transform| transform.translation.xz()),
+                );
+                match mine {
+                    Some(mine) => {
+                        let missing = workers.saturating_sub(army.gatherers) as usize;
+                        let units: Vec<UnitId> =
+                            army.idle_soldiers.iter().take(missing).copied().collect();
+                        let sent = units.len() as u32;
+                        if sent > 0 {
+                            order(PlayerCommand::Gather { units, node: mine });
+                        }
+                        army.gatherers + sent >= *workers
+                    }
+                    None => true,
+                }
+            }
+            BuildStep::Scout => {
+                let target = closest_to(
+                    home,
+                    enemies
+                        .iter()
+                        .map(|enemy| match_settings.map.start_positions[enemy.0 as usize]),
+                );
+                match (army.idle_soldiers.first(), target) {
+                    (Some(scout), Some(target)) => {
+                        order(PlayerCommand::Move {
+                            units: vec![*scout],
+                            target,
+                        });
+                        true
+                    }
+                    (_, None) => true,
+                    (None, _) => false,
+                }
+            }
+            BuildStep::Expand => {
+                let target = closest_to(
+                    home,
+                    sites_query
+                        .iter()
+                        .filter(|(site, _)| site.claimed_by.is_none())
+                        .map(|(_, transform)| transform.translation.xz()),
+                );
+                match (army.idle_soldiers.first(), target) {
+                    (Some(settler), Some(target)) => {
+                        order(PlayerCommand::Move {
+                            units: vec![*settler],
+                            target,
+                        });
+                        true
+                    }
+                    (_, None) => true,
+                    (None, _) => false,
+                }
+            }
+            BuildStep::AttackWave { army_size } => {
+                let target = closest_to(
+                    home,
+                    units_query
+                        .iter()
+                        .filter(|(_, owner, ..)| enemies.contains(&owner.0))
+                        .map(|(_, _, transform, ..)| transform.translation.xz()),
+                );
+                // Buildings are only besieged once nobody is left to defend them
+                let building = buildings_query
+                    .iter()
+                    .filter(|(_, owner, _)| enemies.contains(&owner.0))
+                    .min_by(|(a_id, _, a), (b_id, _, b)| {
+                        a.translation
+                            .xz()
+                            .distance_squared(home)
+                            .total_cmp(&b.translation.xz().distance_squared(home))
+                            .then(a_id.cmp(b_id))
+                    })
+                    .map(|(id, ..)| *id);
+                let units = army.idle_soldiers.clone();
+                let command = match (target, building) {
+                    (Some(target), _) => Some(PlayerCommand::Move { units, target }),
+                    (None, Some(target)) => Some(PlayerCommand::Attack { units, target }),
+                    (None, None) => None,
+                };
+
+                match command {
+                    Some(command) if army.idle_soldiers.len() as u32 >= *army_size => {
+                        info!(
+                            "Player {} attacks with {} soldiers",
+                            player.id.0 + 1,
+                            army.idle_soldiers.len()
+                        );
+                        order(command);
+                        true
+                    }
+                    Some(_) => false,
+                    None => true,
+                }
+            }
+        };
+
+        if done {
+            controller.step += 1;
+            if controller.step >= ai_config.build_order.len() {
+                controller.step = ai_config.repeat_from;
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Plays a match without a window and prints a JSON report of how it went, for balance runs on
+//! machines without a GPU.
+//!
+//! ```text
+//! simulate [--ticks N] [--seed SEED] [--scenario FILE] [--output FILE]
+//! ```
+//!
+//! A scenario is a replay file, whose commands are played at their tick. Without one, the default
+//! match is played with computer players in every open slot.
+
+use std::{env, fs, path::PathBuf};
+
+use anyhow::{Context, Error, bail};
+use rts_game_rs::{
+    cli::LaunchOptions,
+    headless::{DEFAULT_TICKS, run_scenario},
+    replay::Replay,
+};
+
+struct Options {
+    ticks: u64,
+    seed: Option<u64>,
+    scenario: Option<PathBuf>,
+    output: Option<PathBuf>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, Error> {
+        let mut options = Options {
+            ticks: DEFAULT_TICKS,
+            seed: None,
+            scenario: None,
+            output: None,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .with_context(|| format!("Missing value for {arg}"))
+            };
+            match arg.as_str() {
+                "--ticks" => options.ticks = value()?.parse()?,
+                "--seed" => options.seed = Some(value()?.parse()?),
+                "--scenario" => options.scenario = Some(value()?.into()),
+                "--output" => options.output = Some(value()?.into()),
+                _ => bail!("Unknown argument {arg}"),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let options = Options::parse(env::args().skip(1))?;
+
+    let replay = options
+        .scenario
+        .as_ref()
+        .map(|path| {
+            Replay::load(path)
+                .with_context(|| format!("Could not load scenario {}", path.display()))
+        })
+        .transpose()?;
+    // Played the same way as `rts-game-rs --headless` with default settings
+    let mut scenario = LaunchOptions {
+        replay,
+        ..LaunchOptions::default()
+    }
+    .scenario();
+    if let Some(seed) = options.seed {
+        scenario.settings.seed = seed;
+    }
+
+    let report = serde_json::to_string_pretty(&run_scenario(scenario, options.ticks))?;
+    match options.output {
+        Some(path) => fs::write(path, report)?,
+        None => println!("{report}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use crate::{
+    config::settings::Settings,
+    game_states::GameState,
+    input::Action,
+    match_settings::MatchSettings,
+    orders::{
+        CommandTarget, LocalCommands, OrderGiven, OrderKind, OrderTargeting, PlayerCommand, UnitId,
+        complete_order_targeting,
+    },
+    picking::PickShape,
+    players::{LocalPlayer, Owner, Player, spawn_players},
+    save::LoadedSave,
+    units::{Selected, Unit, health::Health},
+};
+
+const BASE_SIZE: Vec3 = Vec3::new(0.6, 0.4, 0.6);
+
+pub struct BuildingsPlugin;
+
+impl Plugin for BuildingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Building>().add_systems(
+            OnEnter(GameState::Playing),
+            spawn_bases
+                .after(spawn_players)
+                .run_if(not(resource_exists::<LoadedSave>)),
+        );
+    }
+}
+
+/// Something a player owns that stays where it was put. Buildings are numbered with a [`UnitId`]
+/// so that attack orders can be aimed at them, but they are not a [`Unit`]: they don't move, fight
+/// or get picked by the tactical layer.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct Building;
+
+/// Gives the building its mesh and makes it clickable.
+/// Everything added here is rebuilt rather than saved, see [`crate::save`].
+pub(crate) fn attach_base(
+    building: &mut EntityCommands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    color: Color,
+) {
+    building
+        .insert((
+            StateScoped(GameState::Playing),
+            Mesh3d(meshes.add(Cuboid::from_size(BASE_SIZE))),
+            MeshMaterial3d(materials.add(color)),
+            PickShape::Unit {
+                radius: BASE_SIZE.x / 2.,
+                half_height: BASE_SIZE.y / 2.,
+            },
+        ))
+        .observe(on_click);
+}
+
+/// Every player starts with a base on its start position.
+fn spawn_bases(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    players_query: Query<&Player>,
+    match_settings: Res<MatchSettings>,
+) {
+    for player in players_query.iter() {
+        let start = match_settings.map.start_positions[player.id.0 as usize];
+
+        let mut base = commands.spawn((
+            Name::new(format!("Base {}", player.id.0 + 1)),
+            Building,
+            Owner(player.id),
+            Transform::from_translation(Vec3::new(start.x, BASE_SIZE.y / 2., start.y)),
+            Health::new(500.),
+        ));
+        attach_base(&mut base, &mut meshes, &mut materials, player.color.color());
+    }
+}
+
+/// Buildings cannot be selected, clicking one only aims orders at it.
+fn on_click(
+    click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    buildings_query: Query<(&UnitId, &Owner), With<Building>>,
+    selected_query: Query<&UnitId, (With<Selected>, With<Unit>)>,
+    order_targeting: Option<Res<OrderTargeting>>,
+    mut local_commands: ResMut<LocalCommands>,
+    settings: Res<Settings>,
+    local_player: Res<LocalPlayer>,
+) {
+    // Numbered on the first tick after they spawn, like units
+    let Ok((target, owner)) = buildings_query.get(click.target) else {
+        return;
+    };
+    let units: Vec<UnitId> = selected_query.iter().copied().collect();
+
+    match settings.input.pointer_action(click.button) {
+        Some(Action::Select) => {
+            complete_order_targeting(
+                &mut commands,
+                order_targeting,
+                &mut local_commands,
+                units,
+                CommandTarget::Unit(*target),
+            );
+        }
+        Some(Action::Command) if owner.0 != local_player.0 && !units.is_empty() => {
+            commands.trigger(OrderGiven {
+                kind: OrderKind::Attack,
+                target: CommandTarget::Unit(*target),
+            });
+            local_commands.0.push(PlayerCommand::Attack {
+                units,
+                target: *target,
+            });
+        }
+        _ => (),
+    }
+}
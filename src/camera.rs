@@ -1,19 +1,35 @@
 use bevy::{input::mouse::AccumulatedMouseScroll, prelude::*, render::camera::ScalingMode};
-use bevy_rapier3d::prelude::RapierPickable;
 
-use crate::config::camera::CameraConfig;
+use crate::{
+    config::camera::CameraConfig, game_states::GameState, input::Action,
+    match_settings::MatchSettings, players::LocalPlayer, save::LoadedSave,
+};
+
+/// Where the camera stands relative to the point it looks at.
+const CAMERA_OFFSET: Vec3 = Vec3::new(5.0, 5.0, 5.0);
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup)
+        app.register_type::<MainCamera>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                focus_local_player.run_if(not(resource_exists::<LoadedSave>)),
+            )
             .add_systems(Update, (zoom, movement_keyboard));
     }
 }
 
+/// The player's camera, also used to tag its saved position, see [`crate::save`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct MainCamera;
+
 fn setup(mut commands: Commands, camera_settings: Res<CameraConfig>) {
     commands.spawn((
+        MainCamera,
         Camera3d::default(),
         Projection::from(OrthographicProjection {
             // We can set the scaling mode to FixedVertical to keep the viewport height constant as its aspect ratio changes.
@@ -25,15 +41,23 @@ fn setup(mut commands: Commands, camera_settings: Res<CameraConfig>) {
             scale: 1.,
             ..OrthographicProjection::default_3d()
         }),
-        Transform::from_xyz(5.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-        RapierPickable,
+        Transform::from_translation(CAMERA_OFFSET).looking_at(Vec3::ZERO, Vec3::Y),
     ));
 }
 
+fn focus_local_player(
+    mut camera: Single<&mut Transform, With<MainCamera>>,
+    match_settings: Res<MatchSettings>,
+    local_player: Res<LocalPlayer>,
+) {
+    let start = match_settings.map.start_positions[local_player.0.0 as usize];
+    camera.translation = Vec3::new(start.x, 0., start.y) + CAMERA_OFFSET;
+}
+
 fn movement_keyboard(
     camera_query: Single<(&mut Transform, &mut Projection), With<Camera3d>>,
     camera_config: Res<CameraConfig>,
-    key: Res<ButtonInput<KeyCode>>,
+    actions: Res<ButtonInput<Action>>,
     time: Res<Time>,
 ) -> Result {
     let (mut camera, mut projection) = camera_query.into_inner();
@@ -55,16 +79,16 @@ fn movement_keyboard(
         Projection::Custom(_) => (),
     }
 
-    if key.pressed(KeyCode::ArrowDown) {
+    if actions.pressed(Action::CameraPanDown) {
         camera.translation -= forward_unit * time.delta_secs() * speed;
     }
-    if key.pressed(KeyCode::ArrowUp) {
+    if actions.pressed(Action::CameraPanUp) {
         camera.translation += forward_unit * time.delta_secs() * speed;
     }
-    if key.pressed(KeyCode::ArrowLeft) {
+    if actions.pressed(Action::CameraPanLeft) {
         camera.translation += left_unit * time.delta_secs() * speed;
     }
-    if key.pressed(KeyCode::ArrowRight) {
+    if actions.pressed(Action::CameraPanRight) {
         camera.translation -= left_unit * time.delta_secs() * speed;
     }
 
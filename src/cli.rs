@@ -0,0 +1,173 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, bail};
+use bevy::prelude::*;
+
+use crate::{
+    game_states::GameState,
+    headless::DEFAULT_TICKS,
+    match_settings::{MAX_PLAYERS, MapDefinition, MatchSettings, SlotKind, random_seed},
+    replay::{Replay, ReplayPlayback},
+};
+
+pub const USAGE: &str = "\
+Usage: rts-game-rs [OPTIONS]
+
+Options:
+  --state <STATE>      State to open once loaded: start-menu, game-selection, load-game, replays,
+                       playing, map-editor, hero-editor, options or credits
+  --map <MAP>          Name of a builtin map or path to a RON map definition
+  --seed <SEED>        Seed of the match, random when not given
+  --players <KINDS>    Comma separated slot kinds (human, ai or closed), e.g. human,ai,ai
+  --replay <FILE>      Watches a recorded match
+  --headless           Plays the match without a window and prints a report
+  --ticks <TICKS>      Ticks to simulate in headless mode
+  --stress <UNITS>     Adds this many idle soldiers to the match and logs the frame time
+  --help               Prints this message";
+
+/// How the game was asked to start from the command line.
+#[derive(Debug)]
+pub struct LaunchOptions {
+    /// State shown once assets are loaded
+    pub state: GameState,
+    pub match_settings: MatchSettings,
+    pub replay: Option<Replay>,
+    pub headless: bool,
+    pub ticks: u64,
+    /// Number of units of the stress scene, see [`crate::stress::StressTestPlugin`]
+    pub stress_units: Option<usize>,
+    pub help: bool,
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        Self {
+            state: GameState::StartMenu,
+            match_settings: MatchSettings::default(),
+            replay: None,
+            headless: false,
+            ticks: DEFAULT_TICKS,
+            stress_units: None,
+            help: false,
+        }
+    }
+}
+
+impl LaunchOptions {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut options = LaunchOptions::default();
+        let mut seed = None;
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .with_context(|| format!("Missing value for {arg}"))
+            };
+            match arg.as_str() {
+                "--state" => options.state = parse_state(&value()?)?,
+                "--map" => options.match_settings.map = parse_map(&value()?)?,
+                "--seed" => seed = Some(value()?.parse()?),
+                "--players" => parse_players(&value()?, &mut options.match_settings)?,
+                "--replay" => {
+                    let path = PathBuf::from(value()?);
+                    let replay = Replay::load(&path)
+                        .with_context(|| format!("Could not load {}", path.display()))?;
+                    options.replay = Some(replay);
+                }
+                "--headless" => options.headless = true,
+                "--ticks" => options.ticks = value()?.parse()?,
+                "--stress" => options.stress_units = Some(value()?.parse()?),
+                "--help" | "-h" => options.help = true,
+                _ => bail!("Unknown argument {arg}\n\n{USAGE}"),
+            }
+        }
+
+        // A replay is played as it was recorded, whatever the other options say
+        if let Some(replay) = &options.replay {
+            options.match_settings = replay.settings.clone();
+            options.state = GameState::Playing;
+        } else {
+            options.match_settings.seed = seed.unwrap_or_else(random_seed);
+        }
+
+        Ok(options)
+    }
+
+    /// What headless mode plays: the replay if one was given, otherwise the match settings with
+    /// computer players in place of humans, as nobody is there to give orders.
+    pub fn scenario(&self) -> Replay {
+        if let Some(replay) = &self.replay {
+            return replay.clone();
+        }
+
+        let mut settings = self.match_settings.clone();
+        for slot in settings.slots.iter_mut() {
+            if slot.kind == SlotKind::Human {
+                slot.kind = SlotKind::Ai;
+            }
+        }
+        Replay::new(settings)
+    }
+}
+
+/// Applies the match settings and replay chosen on the command line, the initial state is set
+/// on [`crate::game_states::LoadingPlugin`].
+pub struct LaunchPlugin {
+    pub match_settings: MatchSettings,
+    pub replay: Option<Replay>,
+}
+
+impl Plugin for LaunchPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.match_settings.clone());
+        if let Some(replay) = &self.replay {
+            app.insert_resource(ReplayPlayback(replay.clone()));
+        }
+    }
+}
+
+fn parse_state(name: &str) -> anyhow::Result<GameState> {
+    Ok(match name {
+        "start-menu" => GameState::StartMenu,
+        "game-selection" => GameState::GameSelection,
+        "load-game" => GameState::LoadGame,
+        "replays" => GameState::Replays,
+        "playing" => GameState::Playing,
+        "map-editor" => GameState::MapEditor,
+        "hero-editor" => GameState::HeroEditor,
+        "options" => GameState::Options,
+        "credits" => GameState::Credits,
+        _ => bail!("Unknown state {name}"),
+    })
+}
+
+fn parse_map(name_or_path: &str) -> anyhow::Result<MapDefinition> {
+    if let Some(map) = MapDefinition::builtin()
+        .into_iter()
+        .find(|map| map.name.eq_ignore_ascii_case(name_or_path))
+    {
+        return Ok(map);
+    }
+
+    let contents = fs::read_to_string(name_or_path)
+        .with_context(|| format!("{name_or_path} is neither a builtin map nor a readable file"))?;
+    Ok(ron::from_str(&contents)?)
+}
+
+fn parse_players(kinds: &str, match_settings: &mut MatchSettings) -> anyhow::Result<()> {
+    let kinds: Vec<&str> = kinds.split(',').map(str::trim).collect();
+    if kinds.len() > MAX_PLAYERS {
+        bail!("At most {MAX_PLAYERS} players can take part in a match");
+    }
+
+    for (index, slot) in match_settings.slots.iter_mut().enumerate() {
+        slot.kind = match kinds.get(index) {
+            Some(&"human") => SlotKind::Human,
+            Some(&"ai") => SlotKind::Ai,
+            Some(&"closed") | None => SlotKind::Closed,
+            Some(kind) => bail!("Unknown player kind {kind}"),
+        };
+    }
+
+    Ok(())
+}
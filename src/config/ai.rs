@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{economy::UnitKind, match_settings::Difficulty};
+
+pub struct AiConfigPlugin;
+
+impl Plugin for AiConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AiConfig>().insert_resource(AiConfig {
+            build_order: vec![
+                BuildStep::Train {
+                    unit: UnitKind::Soldier,
+                    count: 4,
+                },
+                BuildStep::Gather { workers: 2 },
+                BuildStep::Scout,
+                BuildStep::Train {
+                    unit: UnitKind::Soldier,
+                    count: 8,
+                },
+                BuildStep::Expand,
+                BuildStep::AttackWave { army_size: 5 },
+                BuildStep::Train {
+                    unit: UnitKind::Soldier,
+                    count: 10,
+                },
+                BuildStep::AttackWave { army_size: 6 },
+            ],
+            repeat_from: 6,
+            easy: DifficultyModifiers {
+                reaction_time: 3.,
+                income_multiplier: 0.75,
+            },
+            normal: DifficultyModifiers {
+                reaction_time: 1.5,
+                income_multiplier: 1.,
+            },
+            hard: DifficultyModifiers {
+                reaction_time: 0.5,
+                income_multiplier: 1.5,
+            },
+        });
+    }
+}
+
+/// One step of a computer player's build order, moved past once it is done.
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+pub enum BuildStep {
+    /// Train units of the given kind until the player owns `count` of them
+    Train { unit: UnitKind, count: u32 },
+    /// Send idle soldiers to the mine of the player until `workers` of them gather
+    Gather { workers: u32 },
+    /// Send a soldier to look at the enemy start positions
+    Scout,
+    /// Send a soldier to hold the closest expansion site nobody has claimed
+    Expand,
+    /// Wait for `army_size` idle soldiers and send them to attack the closest enemy
+    AttackWave { army_size: u32 },
+}
+
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+pub struct DifficultyModifiers {
+    /// Seconds between two decisions of the computer player
+    pub reaction_time: f32,
+    /// Multiplies the resources gathered by the computer player
+    pub income_multiplier: f32,
+}
+
+#[derive(Debug, Resource, Reflect)]
+pub struct AiConfig {
+    pub build_order: Vec<BuildStep>,
+    /// Index of the step the build order goes back to once its last step is done
+    pub repeat_from: usize,
+    pub easy: DifficultyModifiers,
+    pub normal: DifficultyModifiers,
+    pub hard: DifficultyModifiers,
+}
+
+impl AiConfig {
+    pub fn modifiers(&self, difficulty: Difficulty) -> &DifficultyModifiers {
+        match difficulty {
+            Difficulty::Easy => &self.easy,
+            Difficulty::Normal => &self.normal,
+            Difficulty::Hard => &self.hard,
+        }
+    }
+}
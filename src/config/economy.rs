@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+
+use crate::economy::UnitKind;
+
+pub struct EconomyConfigPlugin;
+
+impl Plugin for EconomyConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<EconomyConfig>()
+            .insert_resource(EconomyConfig {
+                income_per_second: 10.,
+                expansion_income_bonus: 0.5,
+                expansion_claim_radius: 1.,
+                mine_distance: 1.5,
+                gather_amount: 10,
+                gather_seconds: 2.,
+                gather_reach: 0.5,
+                soldier_cost: 100,
+                hero_cost: 400,
+            });
+    }
+}
+
+#[derive(Debug, Resource, Reflect)]
+pub struct EconomyConfig {
+    /// Resources gathered every second by each player, before any bonus
+    pub income_per_second: f32,
+    /// Fraction of the base income added for every expansion site a player holds
+    pub expansion_income_bonus: f32,
+    /// Distance from an expansion site within which units claim it for their owner
+    pub expansion_claim_radius: f32,
+    /// Distance from a start position to its mine, towards the middle of the map
+    pub mine_distance: f32,
+    /// Resources a gatherer carries back from every trip to its mine
+    pub gather_amount: u32,
+    /// Time a gatherer spends at its mine before carrying a load back
+    pub gather_seconds: f32,
+    /// How close a gatherer has to be to its mine or its start position to mine or drop its load,
+    /// and how close to a mine a click has to be to send gatherers to it
+    pub gather_reach: f32,
+    pub soldier_cost: u32,
+    pub hero_cost: u32,
+}
+
+impl EconomyConfig {
+    pub fn cost(&self, unit: UnitKind) -> u32 {
+        match unit {
+            UnitKind::Soldier => self.soldier_cost,
+            UnitKind::Hero => self.hero_cost,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+
+pub struct HeroConfigPlugin;
+
+impl Plugin for HeroConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<HeroConfig>()
+            .insert_resource(HeroConfig {
+                experience_per_kill: 25,
+                experience_per_level: 100,
+                max_level: 10,
+                health_per_level: 20.,
+                mana_per_level: 10.,
+            });
+    }
+}
+
+#[derive(Debug, Resource, Reflect)]
+pub struct HeroConfig {
+    /// Experience granted to a hero every time it kills a unit
+    pub experience_per_kill: u32,
+    /// Experience needed to go from level `n` to level `n + 1` is `n * experience_per_level`
+    pub experience_per_level: u32,
+    /// Heroes stop gaining experience once this level is reached
+    pub max_level: u32,
+    /// Maximum health added on every level-up
+    pub health_per_level: f32,
+    /// Maximum mana added on every level-up
+    pub mana_per_level: f32,
+}
+
+impl HeroConfig {
+    /// Total experience needed to leave `level`
+    pub fn experience_to_level_up(&self, level: u32) -> u32 {
+        level * self.experience_per_level
+    }
+}
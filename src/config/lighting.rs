@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::simulation::TICKS_PER_SECOND;
+
+pub struct LightingConfigPlugin;
+
+impl Plugin for LightingConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LightingConfig>()
+            .insert_resource(LightingConfig {
+                day_length_seconds: 600.,
+                start_time: 0.3,
+                noon_illuminance: light_consts::lux::FULL_DAYLIGHT,
+                night_illuminance: 500.,
+                day_color: Color::WHITE,
+                dusk_color: Color::srgb(1., 0.6, 0.35),
+                night_color: Color::srgb(0.45, 0.55, 0.9),
+                day_ambient_brightness: 80.,
+                night_ambient_brightness: 20.,
+                dusk_shadow_distance: 1.5,
+                night_sight_multiplier: 0.7,
+            });
+    }
+}
+
+#[derive(Debug, Resource, Reflect)]
+pub struct LightingConfig {
+    /// Length of a whole day and night, the time of day stays at `start_time` when zero
+    pub day_length_seconds: f32,
+    /// Time of day a match starts at, from 0 at midnight to 0.5 at noon
+    pub start_time: f32,
+    /// Illuminance of the sun when it is at its highest
+    pub noon_illuminance: f32,
+    /// Illuminance of the moon, lighting the map from sunset to sunrise
+    pub night_illuminance: f32,
+    pub day_color: Color,
+    /// Colour of the light when the sun or the moon is close to the horizon
+    pub dusk_color: Color,
+    pub night_color: Color,
+    pub day_ambient_brightness: f32,
+    pub night_ambient_brightness: f32,
+    /// Shadows are drawn up to this many times further when the light is low, as they get longer
+    pub dusk_shadow_distance: f32,
+    /// Multiplies the range within which units spot enemies at night, 1 for no effect on gameplay
+    pub night_sight_multiplier: f32,
+}
+
+impl LightingConfig {
+    /// Time of day at `tick`, from 0 at midnight to 0.5 at noon. It only depends on the tick so
+    /// that every machine of a match agrees on it.
+    pub fn time_of_day(&self, tick: u64) -> f32 {
+        if self.day_length_seconds <= 0. {
+            return self.start_time;
+        }
+
+        let days = tick as f64 / (self.day_length_seconds as f64 * TICKS_PER_SECOND);
+        (self.start_time as f64 + days).fract() as f32
+    }
+
+    /// Whether the sun is up at `tick`, from a quarter to three quarters of the day.
+    pub fn is_day(&self, tick: u64) -> bool {
+        (0.25..0.75).contains(&self.time_of_day(tick))
+    }
+
+    /// Simulation code uses this rather than the position of the sun, which relies on
+    /// trigonometry that may differ between machines.
+    pub fn sight_multiplier(&self, tick: u64) -> f32 {
+        if self.is_day(tick) {
+            1.
+        } else {
+            self.night_sight_multiplier
+        }
+    }
+}
@@ -1,14 +1,42 @@
 use bevy::prelude::*;
 
-use crate::config::{camera::CameraConfigPlugin, terrain::TerrainConfigPlugin};
+use crate::config::{
+    ai::AiConfigPlugin, camera::CameraConfigPlugin, economy::EconomyConfigPlugin,
+    hero::HeroConfigPlugin, lighting::LightingConfigPlugin, models::ModelsConfigPlugin,
+    network::NetworkConfigPlugin, picking::PickingConfigPlugin, settings::SettingsPlugin,
+    tactics::TacticsConfigPlugin, victory::VictoryConfigPlugin, weather::WeatherConfigPlugin,
+};
 
+pub mod ai;
 pub mod camera;
-pub mod terrain;
+pub mod economy;
+pub mod hero;
+pub mod lighting;
+pub mod models;
+pub mod network;
+pub mod picking;
+pub mod settings;
+pub mod tactics;
+pub mod victory;
+pub mod weather;
 
 pub struct ConfigPlugin;
 
 impl Plugin for ConfigPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((CameraConfigPlugin, TerrainConfigPlugin));
+        app.add_plugins((
+            AiConfigPlugin,
+            CameraConfigPlugin,
+            EconomyConfigPlugin,
+            HeroConfigPlugin,
+            LightingConfigPlugin,
+            ModelsConfigPlugin,
+            NetworkConfigPlugin,
+            PickingConfigPlugin,
+            SettingsPlugin,
+            TacticsConfigPlugin,
+            VictoryConfigPlugin,
+            WeatherConfigPlugin,
+        ));
     }
 }
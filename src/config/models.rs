@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+use crate::economy::UnitKind;
+
+pub struct ModelsConfigPlugin;
+
+impl Plugin for ModelsConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ModelsConfig>()
+            .insert_resource(ModelsConfig {
+                blend_seconds: 0.2,
+                corpse_seconds: 4.,
+                soldier: UnitModelConfig {
+                    path: "models/soldier.glb".into(),
+                    walk_speed: 1.,
+                    animations: AnimationNames::default(),
+                },
+                hero: UnitModelConfig {
+                    path: "models/hero.glb".into(),
+                    walk_speed: 1.2,
+                    animations: AnimationNames::default(),
+                },
+            });
+    }
+}
+
+#[derive(Debug, Resource, Reflect)]
+pub struct ModelsConfig {
+    /// Time over which a unit eases from one animation into the next
+    pub blend_seconds: f32,
+    /// Time a fallen unit stays on the ground once it starts dying
+    pub corpse_seconds: f32,
+    pub soldier: UnitModelConfig,
+    pub hero: UnitModelConfig,
+}
+
+impl ModelsConfig {
+    pub fn model(&self, unit: UnitKind) -> &UnitModelConfig {
+        match unit {
+            UnitKind::Soldier => &self.soldier,
+            UnitKind::Hero => &self.hero,
+        }
+    }
+}
+
+/// The glTF file drawing an archetype. Units keep their capsule while it loads, or for good if
+/// the file is missing.
+#[derive(Debug, Clone, Reflect)]
+pub struct UnitModelConfig {
+    /// Relative to the `assets` folder
+    pub path: String,
+    /// Speed at which the walk animation matches the ground, it is played faster for faster units
+    pub walk_speed: f32,
+    pub animations: AnimationNames,
+}
+
+/// Names of the animations of a model. Missing ones fall back to the idle animation.
+#[derive(Debug, Clone, Reflect)]
+pub struct AnimationNames {
+    pub idle: String,
+    pub walk: String,
+    pub attack: String,
+    pub die: String,
+}
+
+impl Default for AnimationNames {
+    fn default() -> Self {
+        Self {
+            idle: "idle".into(),
+            walk: "walk".into(),
+            attack: "attack".into(),
+            die: "die".into(),
+        }
+    }
+}
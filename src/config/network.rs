@@ -0,0 +1,66 @@
+use std::{env, net::SocketAddr};
+
+use bevy::prelude::*;
+
+use crate::players::PlayerId;
+
+pub struct NetworkConfigPlugin;
+
+impl Plugin for NetworkConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(NetworkConfig::from_env());
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkMode {
+    /// Single player, commands are played as soon as they are given
+    Offline,
+    /// Plays the local player alone through the lockstep pipeline, to try it without a network
+    Loopback,
+    /// Plays against other instances of the game over UDP
+    Udp {
+        bind: SocketAddr,
+        peers: Vec<SocketAddr>,
+        /// Every player must use the same seed for the simulation to stay in sync
+        seed: u64,
+        /// Slot played from this machine, every player picks a different human slot
+        local_player: PlayerId,
+    },
+}
+
+#[derive(Debug, Resource)]
+pub struct NetworkConfig {
+    pub mode: NetworkMode,
+}
+
+impl NetworkConfig {
+    /// Reads the mode from the environment:
+    /// - `RTS_LOOPBACK=1` for [`NetworkMode::Loopback`]
+    /// - `RTS_BIND=127.0.0.1:7000 RTS_PEERS=127.0.0.1:7001 RTS_PLAYER=1 RTS_SEED=42` for
+    ///   [`NetworkMode::Udp`], `RTS_PEERS` being a comma separated list
+    pub fn from_env() -> Self {
+        let mode = match (env::var("RTS_BIND"), env::var("RTS_PEERS")) {
+            (Ok(bind), Ok(peers)) => Self::udp_mode(&bind, &peers).unwrap_or_else(|error| {
+                error!("Invalid network settings, playing offline: {error}");
+                NetworkMode::Offline
+            }),
+            _ if env::var("RTS_LOOPBACK").is_ok_and(|value| value == "1") => NetworkMode::Loopback,
+            _ => NetworkMode::Offline,
+        };
+
+        Self { mode }
+    }
+
+    fn udp_mode(bind: &str, peers: &str) -> anyhow::Result<NetworkMode> {
+        Ok(NetworkMode::Udp {
+            bind: bind.parse()?,
+            peers: peers
+                .split(',')
+                .map(|peer| peer.trim().parse())
+                .collect::<Result<_, _>>()?,
+            seed: env::var("RTS_SEED").map_or(Ok(0), |seed| seed.parse())?,
+            local_player: PlayerId(env::var("RTS_PLAYER").map_or(Ok(0), |player| player.parse())?),
+        })
+    }
+}
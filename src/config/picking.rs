@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+pub struct PickingConfigPlugin;
+
+impl Plugin for PickingConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PickingConfig>()
+            .insert_resource(PickingConfig {
+                unit_pick_radius: 0.1,
+            });
+    }
+}
+
+#[derive(Debug, Resource, Reflect)]
+pub struct PickingConfig {
+    /// Added to the radius of units when clicking them, so that thin units are easy to hit
+    pub unit_pick_radius: f32,
+}
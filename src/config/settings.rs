@@ -0,0 +1,201 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    audio::{GlobalVolume, Volume},
+    prelude::*,
+    window::{MonitorSelection, PresentMode, PrimaryWindow, VideoModeSelection, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::{config::camera::CameraConfig, input::InputBindings, paths::config_dir};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Settings>()
+            .insert_resource(Settings::load())
+            .add_systems(
+                Update,
+                (
+                    apply_display_settings,
+                    apply_camera_settings,
+                    apply_audio_settings,
+                )
+                    .run_if(resource_changed::<Settings>),
+            );
+    }
+}
+
+/// User preferences, persisted in [`Settings::path`] and applied as soon as they change.
+#[derive(Debug, Clone, PartialEq, Resource, Reflect, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Settings {
+    pub display: DisplaySettings,
+    pub graphics: GraphicsSettings,
+    pub camera: CameraSettings,
+    pub audio: AudioSettings,
+    pub interface: InterfaceSettings,
+    pub input: InputBindings,
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub width: u32,
+    pub height: u32,
+    pub mode: DisplayMode,
+    pub vsync: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            mode: DisplayMode::Windowed,
+            vsync: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize, EnumIter)]
+pub enum DisplayMode {
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    pub shadow_quality: ShadowQuality,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize, EnumIter, Default)]
+pub enum ShadowQuality {
+    Off,
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+/// Mirrors the user tweakable part of [`CameraConfig`].
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraSettings {
+    pub movement_speed: f32,
+    pub orthographic_zoom_speed: f32,
+    pub perspective_zoom_speed: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            movement_speed: 10.0,
+            orthographic_zoom_speed: 0.001,
+            perspective_zoom_speed: 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Linear volume applied to every sound, between 0 and 1
+    pub master_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self { master_volume: 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct InterfaceSettings {
+    pub unit_bars: BarVisibility,
+}
+
+/// Which units get their health, mana and status drawn above them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize, EnumIter, Default)]
+pub enum BarVisibility {
+    #[default]
+    Always,
+    /// Units under the pointer, and the selected ones
+    Hovered,
+    Selected,
+}
+
+impl Settings {
+    pub fn path() -> PathBuf {
+        config_dir().join("settings.yaml")
+    }
+
+    /// Reads the settings file, falling back to the defaults when it is missing or invalid.
+    pub fn load() -> Self {
+        let path = Self::path();
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+
+        serde_yml::from_str(&contents).unwrap_or_else(|error| {
+            warn!("Ignoring invalid settings file {}: {error}", path.display());
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_yml::to_string(self)?)?;
+
+        info!("Settings saved to {}", path.display());
+
+        Ok(())
+    }
+}
+
+fn apply_display_settings(
+    settings: Res<Settings>,
+    window: Option<Single<&mut Window, With<PrimaryWindow>>>,
+) {
+    // Headless apps have no window to configure
+    let Some(mut window) = window else {
+        return;
+    };
+    let display = &settings.display;
+
+    window
+        .resolution
+        .set(display.width as f32, display.height as f32);
+    window.mode = match display.mode {
+        DisplayMode::Windowed => WindowMode::Windowed,
+        DisplayMode::Borderless => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+        DisplayMode::Fullscreen => {
+            WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+        }
+    };
+    window.present_mode = if display.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+}
+
+fn apply_camera_settings(settings: Res<Settings>, mut camera_config: ResMut<CameraConfig>) {
+    camera_config.movement_speed = settings.camera.movement_speed;
+    camera_config.orthographic_zoom_speed = settings.camera.orthographic_zoom_speed;
+    camera_config.perspective_zoom_speed = settings.camera.perspective_zoom_speed;
+}
+
+fn apply_audio_settings(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(GlobalVolume::new(Volume::Linear(
+        settings.audio.master_volume,
+    )));
+}
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+
+use crate::{economy::UnitKind, units::tactics::Stance};
+
+pub struct TacticsConfigPlugin;
+
+impl Plugin for TacticsConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TacticsConfig>()
+            .insert_resource(TacticsConfig {
+                soldier: ArchetypeTactics {
+                    stance: Stance::Aggressive,
+                    acquire_range: 2.,
+                    leash_distance: 3.,
+                    retreat_health: 0.25,
+                },
+                hero: ArchetypeTactics {
+                    stance: Stance::Defensive,
+                    acquire_range: 2.5,
+                    leash_distance: 4.,
+                    retreat_health: 0.35,
+                },
+            });
+    }
+}
+
+/// How units of one kind behave when they have no order from their player.
+#[derive(Debug, Clone, Reflect)]
+pub struct ArchetypeTactics {
+    /// Stance given to newly spawned units
+    pub stance: Stance,
+    /// Distance within which aggressive units pick a target on their own
+    pub acquire_range: f32,
+    /// Units stop chasing once they are this far from where they started fighting
+    pub leash_distance: f32,
+    /// Fraction of maximum health under which units fall back to their start position
+    pub retreat_health: f32,
+}
+
+#[derive(Debug, Resource, Reflect)]
+pub struct TacticsConfig {
+    pub soldier: ArchetypeTactics,
+    pub hero: ArchetypeTactics,
+}
+
+impl TacticsConfig {
+    pub fn archetype(&self, unit: UnitKind) -> &ArchetypeTactics {
+        match unit {
+            UnitKind::Soldier => &self.soldier,
+            UnitKind::Hero => &self.hero,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+
+pub struct VictoryConfigPlugin;
+
+impl Plugin for VictoryConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<VictoryConfig>()
+            .insert_resource(VictoryConfig {
+                hold_point: Vec2::ZERO,
+                hold_point_radius: 1.,
+                hold_point_seconds: 60.,
+                time_limit_seconds: 600.,
+                score_per_kill: 50,
+            });
+    }
+}
+
+#[derive(Debug, Resource, Reflect)]
+pub struct VictoryConfig {
+    /// Where the point to hold stands on every map
+    pub hold_point: Vec2,
+    /// Units within this distance of the point are holding it
+    pub hold_point_radius: f32,
+    /// Time a team has to hold the point on its own to win, it does not need to be in one go
+    pub hold_point_seconds: f32,
+    /// Length of a match played with a time limit, the team with the highest score wins
+    pub time_limit_seconds: f32,
+    /// Score granted for every enemy unit killed, on top of the resources gathered
+    pub score_per_kill: u32,
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::weather::Weather;
+
+pub struct WeatherConfigPlugin;
+
+impl Plugin for WeatherConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<WeatherConfig>()
+            .insert_resource(WeatherConfig {
+                min_duration_seconds: 120.,
+                max_duration_seconds: 300.,
+                clear: WeatherKindConfig {
+                    weight: 4,
+                    speed_multiplier: 1.,
+                    sight_multiplier: 1.,
+                    fog_start: 0.,
+                    fog_end: 0.,
+                    fog_color: Color::srgb(0.7, 0.75, 0.8),
+                    particles: 0,
+                },
+                rain: WeatherKindConfig {
+                    weight: 2,
+                    speed_multiplier: 0.95,
+                    sight_multiplier: 0.9,
+                    fog_start: 12.,
+                    fog_end: 35.,
+                    fog_color: Color::srgb(0.45, 0.5, 0.55),
+                    particles: 600,
+                },
+                fog: WeatherKindConfig {
+                    weight: 1,
+                    speed_multiplier: 1.,
+                    sight_multiplier: 0.6,
+                    fog_start: 6.,
+                    fog_end: 16.,
+                    fog_color: Color::srgb(0.75, 0.78, 0.8),
+                    particles: 0,
+                },
+                snow: WeatherKindConfig {
+                    weight: 1,
+                    speed_multiplier: 0.75,
+                    sight_multiplier: 0.85,
+                    fog_start: 10.,
+                    fog_end: 28.,
+                    fog_color: Color::srgb(0.85, 0.88, 0.92),
+                    particles: 400,
+                },
+            });
+    }
+}
+
+#[derive(Debug, Resource, Reflect)]
+pub struct WeatherConfig {
+    /// Shortest time random weather lasts, on maps that do not schedule their own
+    pub min_duration_seconds: f32,
+    pub max_duration_seconds: f32,
+    pub clear: WeatherKindConfig,
+    pub rain: WeatherKindConfig,
+    pub fog: WeatherKindConfig,
+    pub snow: WeatherKindConfig,
+}
+
+impl WeatherConfig {
+    pub fn get(&self, weather: Weather) -> &WeatherKindConfig {
+        match weather {
+            Weather::Clear => &self.clear,
+            Weather::Rain => &self.rain,
+            Weather::Fog => &self.fog,
+            Weather::Snow => &self.snow,
+        }
+    }
+}
+
+/// How one kind of weather looks and how it affects units.
+#[derive(Debug, Clone, Reflect)]
+pub struct WeatherKindConfig {
+    /// Relative chance of being picked as random weather, never picked when zero
+    pub weight: u32,
+    /// Multiplies the speed of every unit, 1 for no effect on gameplay
+    pub speed_multiplier: f32,
+    /// Multiplies the range within which units spot enemies, on top of the time of day
+    pub sight_multiplier: f32,
+    /// Distance from the camera at which the fog starts to hide the map
+    pub fog_start: f32,
+    /// Distance from the camera past which the fog hides everything, no fog when zero
+    pub fog_end: f32,
+    pub fog_color: Color,
+    /// Number of raindrops or snowflakes falling around the camera
+    pub particles: usize,
+}
@@ -0,0 +1,330 @@
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{ai::AiConfig, economy::EconomyConfig},
+    game_states::GameState,
+    input::Action,
+    match_settings::{MatchSettings, SlotKind},
+    orders::{LocalCommands, PlayerCommand},
+    players::{Owner, Player, PlayerId, PlayerStats},
+    simulation::SimulationSet,
+    units::{
+        MoveTo, Unit, hero::spawn_hero, rendering::UnitAssets, spatial::NearbyUnits, spawn_soldier,
+    },
+};
+
+pub struct EconomyPlugin;
+
+impl Plugin for EconomyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<UnitKind>()
+            .register_type::<ExpansionSite>()
+            .register_type::<ResourceNode>()
+            .register_type::<Gather>()
+            .add_event::<TrainUnit>()
+            .init_resource::<IncomeTimer>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (spawn_expansion_sites, spawn_resource_nodes),
+            )
+            .add_systems(
+                Update,
+                train_hotkey.run_if(
+                    in_state(GameState::Playing).and(input_just_pressed(Action::TrainSoldier)),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (claim_expansion_sites, gather, gather_income, train_units)
+                    .chain()
+                    .in_set(SimulationSet::Economy),
+            );
+    }
+}
+
+/// The kinds of unit a player can pay for.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UnitKind {
+    Soldier,
+    Hero,
+}
+
+/// Request to train a unit for `player` at its start position, paid from its resources.
+/// Human and computer players both go through this event.
+#[derive(Event, Debug)]
+pub struct TrainUnit {
+    pub player: PlayerId,
+    pub unit: UnitKind,
+}
+
+/// A start position nobody plays from. Holding it raises the income of the player whose units
+/// stand on it.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ExpansionSite {
+    pub claimed_by: Option<PlayerId>,
+}
+
+/// A mine next to the start position of a player, which never runs out.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ResourceNode;
+
+/// Order to carry loads from the mine at `node` to the start position of the unit's owner, trip
+/// after trip until the unit is given another order.
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct Gather {
+    /// Where the mine stands rather than which entity it is, mines being rebuilt on every match
+    /// start
+    pub node: Vec2,
+    /// Resources on their way to the start position
+    pub carrying: u32,
+    /// Seconds spent at the mine on the load being mined
+    pub mined: f32,
+}
+
+impl Gather {
+    pub fn new(node: Vec2) -> Self {
+        Self {
+            node,
+            carrying: 0,
+            mined: 0.,
+        }
+    }
+}
+
+/// The mine within `reach` of `point`, if any.
+pub fn node_near(nodes: impl Iterator<Item = Vec2>, point: Vec2, reach: f32) -> Option<Vec2> {
+    nodes
+        .filter(|node| node.distance_squared(point) <= reach * reach)
+        .min_by(|a, b| {
+            a.distance_squared(point)
+                .total_cmp(&b.distance_squared(point))
+                .then(a.x.total_cmp(&b.x))
+                .then(a.y.total_cmp(&b.y))
+        })
+}
+
+#[derive(Resource, Debug)]
+struct IncomeTimer(Timer);
+
+impl Default for IncomeTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(1., TimerMode::Repeating))
+    }
+}
+
+// Sites are rebuilt on every match start, even from a save, since who holds them only depends on
+// where the units stand.
+fn spawn_expansion_sites(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    match_settings: Res<MatchSettings>,
+) {
+    for (index, slot) in match_settings.slots.iter().enumerate() {
+        if slot.kind != SlotKind::Closed {
+            continue;
+        }
+        let position = match_settings.map.start_positions[index];
+
+        commands.spawn((
+            Name::new(format!("Expansion site {}", index + 1)),
+            ExpansionSite { claimed_by: None },
+            StateScoped(GameState::Playing),
+            Mesh3d(meshes.add(Cylinder::new(0.3, 0.02))),
+            MeshMaterial3d(materials.add(Color::srgb_u8(180, 160, 60))),
+            Transform::from_translation(Vec3::new(position.x, 0.01, position.y)),
+        ));
+    }
+}
+
+// Mines are rebuilt on every match start like expansion sites, there is nothing to remember about
+// them.
+fn spawn_resource_nodes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    match_settings: Res<MatchSettings>,
+    economy_config: Res<EconomyConfig>,
+) {
+    for (index, slot) in match_settings.slots.iter().enumerate() {
+        if slot.kind == SlotKind::Closed {
+            continue;
+        }
+        let start = match_settings.map.start_positions[index];
+        let position = start - start.normalize_or_zero() * economy_config.mine_distance;
+
+        commands.spawn((
+            Name::new(format!("Mine {}", index + 1)),
+            ResourceNode,
+            StateScoped(GameState::Playing),
+            Mesh3d(meshes.add(Cuboid::new(0.4, 0.3, 0.4))),
+            MeshMaterial3d(materials.add(Color::srgb_u8(70, 130, 200))),
+            Transform::from_translation(Vec3::new(position.x, 0.15, position.y)),
+        ));
+    }
+}
+
+/// A site changes hands once the units of a single player are standing on it.
+fn claim_expansion_sites(
+    mut sites_query: Query<(&mut ExpansionSite, &Transform)>,
+    owners_query: Query<&Owner, With<Unit>>,
+    nearby_units: NearbyUnits,
+    economy_config: Res<EconomyConfig>,
+) {
+    for (mut site, site_transform) in sites_query.iter_mut() {
+        let site_position = site_transform.translation.xz();
+        let mut owners = owners_query
+            .iter_many(
+                nearby_units.within_radius(site_position, economy_config.expansion_claim_radius),
+            )
+            .map(|owner| owner.0);
+
+        let Some(first) = owners.next() else {
+            continue;
+        };
+        if owners.all(|owner| owner == first) && site.claimed_by != Some(first) {
+            info!("Player {} claimed an expansion site", first.0 + 1);
+            site.claimed_by = Some(first);
+        }
+    }
+}
+
+/// Computer players get more or less out of the same work depending on their difficulty.
+fn income_multiplier(player: &Player, ai_config: &AiConfig, match_settings: &MatchSettings) -> f32 {
+    match player.kind {
+        SlotKind::Ai => {
+            let difficulty = match_settings.slots[player.id.0 as usize].difficulty;
+            ai_config.modifiers(difficulty).income_multiplier
+        }
+        _ => 1.,
+    }
+}
+
+/// Walks gatherers back and forth between their mine and the start position of their owner,
+/// mining a load at one end and paying it at the other.
+fn gather(
+    mut commands: Commands,
+    mut gatherers_query: Query<(Entity, &Owner, &Transform, &mut Gather, Option<&MoveTo>)>,
+    mut players_query: Query<(&mut Player, &mut PlayerStats)>,
+    economy_config: Res<EconomyConfig>,
+    ai_config: Res<AiConfig>,
+    match_settings: Res<MatchSettings>,
+    time: Res<Time>,
+) {
+    for (entity, owner, transform, mut gather, move_to) in gatherers_query.iter_mut() {
+        let home = match_settings.map.start_positions[owner.0.0 as usize];
+        let destination = if gather.carrying == 0 {
+            gather.node
+        } else {
+            home
+        };
+
+        if transform.translation.xz().distance_squared(destination)
+            > economy_config.gather_reach.powi(2)
+        {
+            if move_to.is_none_or(|move_to| move_to.target != destination) {
+                commands.entity(entity).insert(MoveTo {
+                    target: destination,
+                });
+            }
+            continue;
+        }
+
+        if gather.carrying == 0 {
+            gather.mined += time.delta_secs();
+            if gather.mined >= economy_config.gather_seconds {
+                gather.mined = 0.;
+                gather.carrying = economy_config.gather_amount;
+            }
+        } else if let Some((mut player, mut stats)) = players_query
+            .iter_mut()
+            .find(|(player, _)| player.id == owner.0)
+        {
+            let multiplier = income_multiplier(&player, &ai_config, &match_settings);
+            let income = (gather.carrying as f32 * multiplier).round() as u32;
+            player.resources += income;
+            stats.resources_gathered += income;
+            gather.carrying = 0;
+        }
+    }
+}
+
+fn gather_income(
+    mut players_query: Query<(&mut Player, &mut PlayerStats)>,
+    sites_query: Query<&ExpansionSite>,
+    mut income_timer: ResMut<IncomeTimer>,
+    economy_config: Res<EconomyConfig>,
+    ai_config: Res<AiConfig>,
+    match_settings: Res<MatchSettings>,
+    time: Res<Time>,
+) {
+    if !income_timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for (mut player, mut stats) in players_query.iter_mut() {
+        let expansions = sites_query
+            .iter()
+            .filter(|site| site.claimed_by == Some(player.id))
+            .count();
+        let multiplier = income_multiplier(&player, &ai_config, &match_settings);
+
+        let income = economy_config.income_per_second
+            * (1. + economy_config.expansion_income_bonus * expansions as f32)
+            * multiplier;
+        let income = income.round() as u32;
+        player.resources += income;
+        stats.resources_gathered += income;
+    }
+}
+
+fn train_hotkey(mut local_commands: ResMut<LocalCommands>) {
+    local_commands.0.push(PlayerCommand::Train {
+        unit: UnitKind::Soldier,
+    });
+}
+
+fn train_units(
+    mut commands: Commands,
+    mut assets: UnitAssets,
+    mut train_events: EventReader<TrainUnit>,
+    mut players_query: Query<(&mut Player, &mut PlayerStats)>,
+    economy_config: Res<EconomyConfig>,
+    match_settings: Res<MatchSettings>,
+) {
+    for train in train_events.read() {
+        let Some((mut player, mut stats)) = players_query
+            .iter_mut()
+            .find(|(player, _)| player.id == train.player)
+        else {
+            continue;
+        };
+
+        let cost = economy_config.cost(train.unit);
+        if player.resources < cost {
+            info!(
+                "Player {} cannot afford a {:?} ({}/{cost})",
+                train.player.0 + 1,
+                train.unit,
+                player.resources
+            );
+            continue;
+        }
+        player.resources -= cost;
+        stats.units_produced += 1;
+
+        let position = match_settings.map.start_positions[train.player.0 as usize];
+        match train.unit {
+            UnitKind::Soldier => {
+                spawn_soldier(&mut commands, &mut assets, &player, position);
+            }
+            UnitKind::Hero => {
+                spawn_hero(&mut commands, &mut assets, &player, position);
+            }
+        }
+    }
+}
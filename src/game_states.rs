@@ -1,11 +1,40 @@
-use bevy::{dev_tools::states::log_transitions, prelude::*};
+use bevy::prelude::*;
+use bevy_asset_loader::loading_state::{LoadingState, LoadingStateAppExt};
+
+use crate::input::Action;
 
 pub struct GameStatePlugin;
 
 impl Plugin for GameStatePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<GameState>()
-            .add_systems(Update, (log_transitions::<GameState>, exit_game));
+        app.init_state::<GameState>().add_systems(Update, exit_game);
+
+        #[cfg(feature = "dev")]
+        app.add_systems(
+            Update,
+            bevy::dev_tools::states::log_transitions::<GameState>,
+        );
+    }
+}
+
+/// Shows [`GameState::Loading`] until the assets are ready, then moves on to `next_state`.
+pub struct LoadingPlugin {
+    pub next_state: GameState,
+}
+
+impl Default for LoadingPlugin {
+    fn default() -> Self {
+        Self {
+            next_state: GameState::StartMenu,
+        }
+    }
+}
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_loading_state(
+            LoadingState::new(GameState::Loading).continue_to_state(self.next_state),
+        );
     }
 }
 
@@ -17,7 +46,9 @@ pub enum GameState {
     StartMenu,
     GameSelection,
     LoadGame,
+    Replays,
     Playing,
+    PostGame,
     MapEditor,
     HeroEditor,
     Options,
@@ -25,8 +56,8 @@ pub enum GameState {
     ExitingGame,
 }
 
-fn exit_game(key: Res<ButtonInput<KeyCode>>, mut app_exit_event: EventWriter<AppExit>) {
-    if key.pressed(KeyCode::SuperLeft) && key.pressed(KeyCode::Escape) {
+fn exit_game(actions: Res<ButtonInput<Action>>, mut app_exit_event: EventWriter<AppExit>) {
+    if actions.pressed(Action::ExitGame) {
         app_exit_event.write(AppExit::Success);
     }
 }
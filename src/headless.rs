@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::system::RunSystemOnce, input::InputPlugin, prelude::*, state::app::StatesPlugin,
+    time::TimeUpdateStrategy, transform::TransformPlugin,
+};
+use serde::Serialize;
+
+use crate::{
+    GameplayPlugins,
+    game_states::{GameState, GameStatePlugin},
+    match_settings::SlotKind,
+    orders::UnitId,
+    players::{Owner, Player, PlayerId, PlayerStats},
+    replay::{Replay, ReplayPlayback},
+    simulation::{SimulationTick, TICKS_PER_SECOND, restore_translations, state_checksum},
+    units::{Unit, health::Health},
+    victory::MatchResult,
+};
+
+/// Ticks played by headless runs unless told otherwise, ten minutes of play.
+pub const DEFAULT_TICKS: u64 = 10 * 60 * TICKS_PER_SECOND as u64;
+
+/// What [`DefaultPlugins`] provides to the gameplay plugins, without a window, renderer or
+/// audio. Meant to be added on top of [`MinimalPlugins`].
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            AssetPlugin::default(),
+            StatesPlugin,
+            InputPlugin,
+            TransformPlugin,
+        ))
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        // Every update runs exactly one simulation tick, however long it takes
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1. / TICKS_PER_SECOND,
+        )));
+    }
+}
+
+/// Outcome of a headless run.
+#[derive(Debug, Serialize)]
+pub struct SimulationReport {
+    pub ticks: u64,
+    /// [`state_checksum`] after the last tick
+    pub checksum: u64,
+    /// Whether a victory condition was met before running out of ticks
+    pub finished: bool,
+    pub winning_team: Option<u8>,
+    pub players: Vec<PlayerReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerReport {
+    pub id: PlayerId,
+    pub team: u8,
+    pub kind: SlotKind,
+    pub resources: u32,
+    pub units_alive: usize,
+    pub stats: PlayerStats,
+}
+
+/// An app running the gameplay plugins without a window, stepped by hand with [`App::update`].
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        HeadlessPlugin,
+        GameStatePlugin,
+        GameplayPlugins,
+    ));
+    app.finish();
+    app.cleanup();
+
+    app
+}
+
+/// Plays `scenario` for at most `ticks` ticks, stopping early if the match ends.
+pub fn run_scenario(scenario: Replay, ticks: u64) -> SimulationReport {
+    let mut app = headless_app();
+    app.insert_resource(scenario.settings.clone())
+        .insert_resource(ReplayPlayback(scenario));
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+
+    loop {
+        app.update();
+
+        // Units are despawned when leaving the match, the report is made before that happens
+        let world = app.world();
+        if world.resource::<SimulationTick>().0 >= ticks || world.contains_resource::<MatchResult>()
+        {
+            break;
+        }
+    }
+
+    report(app.world_mut())
+}
+
+fn report(world: &mut World) -> SimulationReport {
+    // Units are drawn between ticks after every update, the checksum is taken where the
+    // simulation left them like replays do
+    world
+        .run_system_once(restore_translations)
+        .expect("restoring translations should not fail");
+
+    let ticks = world.resource::<SimulationTick>().0;
+    let winning_team = world
+        .get_resource::<MatchResult>()
+        .map(|result| result.winning_team);
+
+    let mut units_query = world.query::<(&UnitId, &Transform, &Health)>();
+    let mut players_query = world.query::<&Player>();
+    let checksum = state_checksum(units_query.iter(world), players_query.iter(world));
+
+    let mut owners_query = world.query_filtered::<&Owner, With<Unit>>();
+    let owners: Vec<PlayerId> = owners_query.iter(world).map(|owner| owner.0).collect();
+
+    let mut players: Vec<PlayerReport> = world
+        .query::<(&Player, &PlayerStats)>()
+        .iter(world)
+        .map(|(player, stats)| PlayerReport {
+            id: player.id,
+            team: player.team,
+            kind: player.kind,
+            resources: player.resources,
+            units_alive: owners.iter().filter(|owner| **owner == player.id).count(),
+            stats: stats.clone(),
+        })
+        .collect();
+    players.sort_by_key(|player| player.id);
+
+    SimulationReport {
+        ticks,
+        checksum,
+        finished: winning_team.is_some(),
+        winning_team: winning_team.flatten(),
+        players,
+    }
+}
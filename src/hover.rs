@@ -0,0 +1,198 @@
+use bevy::{
+    picking::{hover::HoverMap, pointer::PointerId},
+    prelude::*,
+    window::{PrimaryWindow, SystemCursorIcon},
+    winit::cursor::CursorIcon,
+};
+
+use crate::{
+    config::economy::EconomyConfig,
+    economy::ExpansionSite,
+    game_states::GameState,
+    orders::OrderTargeting,
+    players::{LocalPlayer, Owner},
+    terrain::Terrain,
+    units::{Selected, Unit, UnitSelector, hero::AbilityTargeting},
+};
+
+pub struct HoverPlugin;
+
+impl Plugin for HoverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), spawn_hover_ring)
+            .add_systems(OnExit(GameState::Playing), reset_cursor)
+            .add_systems(
+                Update,
+                (highlight_hovered_unit, update_cursor).run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Drawn around the hovered unit with the mesh of its [`UnitSelector`], in its own colours so
+/// that it does not read as a selection.
+#[derive(Component)]
+struct HoverRing {
+    own: Handle<StandardMaterial>,
+    enemy: Handle<StandardMaterial>,
+}
+
+/// What the mouse is over, ignoring entities hidden behind the interface.
+struct PointerHit {
+    unit: Option<Entity>,
+    terrain: Option<Vec3>,
+    interface: bool,
+}
+
+fn pointer_hit(
+    hover_map: &HoverMap,
+    units_query: &Query<&Owner, With<Unit>>,
+    terrain_query: &Query<(), With<Terrain>>,
+    nodes_query: &Query<(), With<Node>>,
+) -> PointerHit {
+    let mut pointer_hit = PointerHit {
+        unit: None,
+        terrain: None,
+        interface: false,
+    };
+    let Some(hits) = hover_map.get(&PointerId::Mouse) else {
+        return pointer_hit;
+    };
+
+    for (entity, hit) in hits.iter() {
+        if nodes_query.contains(*entity) {
+            pointer_hit.interface = true;
+        } else if units_query.contains(*entity) {
+            pointer_hit.unit = Some(*entity);
+        } else if terrain_query.contains(*entity) {
+            pointer_hit.terrain = hit.position;
+        }
+    }
+
+    pointer_hit
+}
+
+fn spawn_hover_ring(mut commands: Commands, mut materials: ResMut<Assets<StandardMaterial>>) {
+    let own = materials.add(Color::srgb_u8(200, 255, 200));
+    let enemy = materials.add(Color::srgb_u8(255, 80, 60));
+
+    commands.spawn((
+        Name::new("HoverRing"),
+        StateScoped(GameState::Playing),
+        Mesh3d::default(),
+        MeshMaterial3d(own.clone()),
+        HoverRing { own, enemy },
+        Transform::default(),
+        Visibility::Hidden,
+    ));
+}
+
+fn highlight_hovered_unit(
+    ring: Single<
+        (
+            &HoverRing,
+            &mut Mesh3d,
+            &mut MeshMaterial3d<StandardMaterial>,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        Without<UnitSelector>,
+    >,
+    hover_map: Res<HoverMap>,
+    units_query: Query<&Owner, With<Unit>>,
+    terrain_query: Query<(), With<Terrain>>,
+    nodes_query: Query<(), With<Node>>,
+    selected_query: Query<(), With<Selected>>,
+    children_query: Query<&Children>,
+    selectors_query: Query<(&Mesh3d, &GlobalTransform), With<UnitSelector>>,
+    local_player: Res<LocalPlayer>,
+) {
+    let (ring, mut ring_mesh, mut ring_material, mut transform, mut visibility) = ring.into_inner();
+    let hit = pointer_hit(&hover_map, &units_query, &terrain_query, &nodes_query);
+
+    // Selected units already show their own ring
+    let selector = hit
+        .unit
+        .filter(|unit| !selected_query.contains(*unit))
+        .and_then(|unit| {
+            children_query
+                .iter_descendants(unit)
+                .find_map(|child| selectors_query.get(child).ok())
+                .map(|selector| (unit, selector))
+        });
+    let Some((unit, (mesh, selector_transform))) = selector else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+
+    let material = if units_query.get(unit).ok() == Some(&Owner(local_player.0)) {
+        ring.own.clone()
+    } else {
+        ring.enemy.clone()
+    };
+    ring_mesh.set_if_neq(mesh.clone());
+    ring_material.set_if_neq(MeshMaterial3d(material));
+    transform.set_if_neq(selector_transform.compute_transform());
+    visibility.set_if_neq(Visibility::Inherited);
+}
+
+/// Shows what a click would do: select over own units, attack over enemies, gather over
+/// expansion sites, move over the rest of the terrain and nothing outside of it.
+fn update_cursor(
+    mut commands: Commands,
+    window: Single<(Entity, Option<&CursorIcon>), With<PrimaryWindow>>,
+    hover_map: Res<HoverMap>,
+    units_query: Query<&Owner, With<Unit>>,
+    terrain_query: Query<(), With<Terrain>>,
+    nodes_query: Query<(), With<Node>>,
+    selected_query: Query<(), (With<Selected>, With<Unit>)>,
+    sites_query: Query<&Transform, With<ExpansionSite>>,
+    ability_targeting: Option<Res<AbilityTargeting>>,
+    order_targeting: Option<Res<OrderTargeting>>,
+    local_player: Res<LocalPlayer>,
+    economy_config: Res<EconomyConfig>,
+) {
+    let hit = pointer_hit(&hover_map, &units_query, &terrain_query, &nodes_query);
+    let targeting = ability_targeting.is_some() || order_targeting.is_some();
+    let commanding = targeting || !selected_query.is_empty();
+
+    let icon = if hit.interface {
+        SystemCursorIcon::Default
+    } else if let Some(unit) = hit.unit {
+        let own = units_query.get(unit).ok() == Some(&Owner(local_player.0));
+        if targeting || (commanding && !own) {
+            SystemCursorIcon::Crosshair
+        } else {
+            SystemCursorIcon::Pointer
+        }
+    } else if let Some(position) = hit.terrain {
+        let on_site = sites_query.iter().any(|site| {
+            site.translation.xz().distance(position.xz()) < economy_config.expansion_claim_radius
+        });
+        if targeting {
+            SystemCursorIcon::Crosshair
+        } else if !commanding {
+            SystemCursorIcon::Default
+        } else if on_site {
+            SystemCursorIcon::Grab
+        } else {
+            SystemCursorIcon::Move
+        }
+    } else if commanding {
+        SystemCursorIcon::NotAllowed
+    } else {
+        SystemCursorIcon::Default
+    };
+
+    // Inserting the cursor every frame would make the window update it every frame
+    let (window, cursor) = window.into_inner();
+    let icon = CursorIcon::from(icon);
+    if cursor != Some(&icon) {
+        commands.entity(window).insert(icon);
+    }
+}
+
+fn reset_cursor(mut commands: Commands, window: Single<Entity, With<PrimaryWindow>>) {
+    commands
+        .entity(*window)
+        .insert(CursorIcon::from(SystemCursorIcon::Default));
+}
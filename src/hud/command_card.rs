@@ -0,0 +1,234 @@
+use bevy::prelude::*;
+
+use crate::{
+    config::{economy::EconomyConfig, settings::Settings},
+    economy::UnitKind,
+    game_states::GameState,
+    hud::{HudSet, SelectionChanged},
+    input::Action,
+    menus::{HOVERED_BUTTON, InteractionPalette, NORMAL_BUTTON, PRESSED_BUTTON},
+    orders::{LocalCommands, OrderTargeting, PlayerCommand, UnitId},
+    players::{LocalPlayer, Player},
+    units::{
+        Movement, Selected, Unit,
+        combat::Weapon,
+        hero::{Abilities, AbilityTargeting, Hero, Mana, use_ability},
+        tactics::Stance,
+    },
+};
+
+const UNAVAILABLE_TEXT: Color = Color::srgb(0.45, 0.45, 0.45);
+
+pub struct CommandCardPlugin;
+
+impl Plugin for CommandCardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                rebuild
+                    .in_set(HudSet::Rebuild)
+                    .run_if(on_event::<SelectionChanged>),
+                update_availability.after(HudSet::Rebuild),
+            )
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Buttons for what the selected units can do, each doing the same as its hotkey.
+#[derive(Component)]
+pub struct CommandCard;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CardCommand {
+    Move,
+    Stop,
+    Attack,
+    CycleStance,
+    Train(UnitKind),
+    /// Index in the [`Abilities`] of the first selected hero
+    Ability(usize),
+}
+
+#[derive(Component)]
+struct CardLabel(CardCommand);
+
+fn rebuild(
+    mut commands: Commands,
+    card: Single<Entity, With<CommandCard>>,
+    selected_query: Query<(Has<Movement>, Has<Weapon>, Has<Stance>), (With<Selected>, With<Unit>)>,
+    heroes: Query<&Abilities, (With<Hero>, With<Selected>)>,
+    settings: Res<Settings>,
+    economy_config: Res<EconomyConfig>,
+) {
+    let card = card.into_inner();
+    commands.entity(card).despawn_related::<Children>();
+
+    let (can_move, can_attack, has_stance) = selected_query.iter().fold(
+        (false, false, false),
+        |(can_move, can_attack, has_stance), (moves, attacks, stance)| {
+            (
+                can_move || moves,
+                can_attack || attacks,
+                has_stance || stance,
+            )
+        },
+    );
+
+    let mut buttons = Vec::new();
+    if can_move {
+        buttons.push((CardCommand::Move, "Move".to_string(), Action::Move));
+        buttons.push((CardCommand::Stop, "Stop".to_string(), Action::Stop));
+    }
+    if can_attack {
+        buttons.push((CardCommand::Attack, "Attack".to_string(), Action::Attack));
+    }
+    if has_stance {
+        buttons.push((
+            CardCommand::CycleStance,
+            "Stance".to_string(),
+            Action::CycleStance,
+        ));
+    }
+    // Units are trained by the player rather than by a building, so it is always offered
+    buttons.push((
+        CardCommand::Train(UnitKind::Soldier),
+        format!("Soldier ({})", economy_config.cost(UnitKind::Soldier)),
+        Action::TrainSoldier,
+    ));
+    // With several heroes selected, abilities are the first one's like with hotkeys
+    if let Some(abilities) = heroes.iter().next() {
+        for (index, ability) in abilities.0.iter().enumerate() {
+            if let Some(hotkey) = ability.hotkey {
+                buttons.push((CardCommand::Ability(index), ability.name.clone(), hotkey));
+            }
+        }
+    }
+
+    commands.entity(card).with_children(|parent| {
+        for (command, name, action) in buttons {
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(NORMAL_BUTTON),
+                    InteractionPalette {
+                        none: NORMAL_BUTTON,
+                        hovered: HOVERED_BUTTON,
+                        pressed: PRESSED_BUTTON,
+                    },
+                    children![
+                        (
+                            CardLabel(command),
+                            Text::new(name),
+                            TextFont {
+                                font_size: 16.0,
+                                ..default()
+                            },
+                        ),
+                        (
+                            Text::new(settings.input.chord(action).to_string()),
+                            TextFont {
+                                font_size: 12.0,
+                                ..default()
+                            },
+                            TextColor(UNAVAILABLE_TEXT),
+                        ),
+                    ],
+                ))
+                .observe(move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                    commands.run_system_cached_with(run_card_command, command);
+                });
+        }
+    });
+}
+
+fn run_card_command(
+    In(command): In<CardCommand>,
+    mut commands: Commands,
+    selected_query: Query<&UnitId, (With<Selected>, With<Unit>)>,
+    heroes: Query<(&UnitId, &Abilities, &Mana), (With<Hero>, With<Selected>)>,
+    mut local_commands: ResMut<LocalCommands>,
+) {
+    let units = || -> Vec<UnitId> { selected_query.iter().copied().collect() };
+
+    match command {
+        CardCommand::Move | CardCommand::Attack => {
+            commands.remove_resource::<AbilityTargeting>();
+            commands.insert_resource(if command == CardCommand::Move {
+                OrderTargeting::Move
+            } else {
+                OrderTargeting::Attack
+            });
+        }
+        CardCommand::Stop => {
+            local_commands
+                .0
+                .push(PlayerCommand::Stop { units: units() });
+        }
+        CardCommand::CycleStance => {
+            local_commands
+                .0
+                .push(PlayerCommand::CycleStance { units: units() });
+        }
+        CardCommand::Train(unit) => {
+            local_commands.0.push(PlayerCommand::Train { unit });
+        }
+        CardCommand::Ability(index) => {
+            let Some((caster, abilities, mana)) = heroes.iter().next() else {
+                return;
+            };
+            if let Some(ability) = abilities.0.get(index) {
+                use_ability(
+                    &mut commands,
+                    &mut local_commands,
+                    *caster,
+                    index,
+                    ability,
+                    mana,
+                );
+            }
+        }
+    }
+}
+
+/// Greys out abilities on cooldown or lacking mana, and units the player cannot afford.
+fn update_availability(
+    mut labels_query: Query<(&mut TextColor, &CardLabel)>,
+    heroes: Query<(&Abilities, &Mana), (With<Hero>, With<Selected>)>,
+    players_query: Query<&Player>,
+    local_player: Res<LocalPlayer>,
+    economy_config: Res<EconomyConfig>,
+) {
+    let resources = players_query
+        .iter()
+        .find(|player| player.id == local_player.0)
+        .map_or(0, |player| player.resources);
+    let hero = heroes.iter().next();
+
+    for (mut color, label) in labels_query.iter_mut() {
+        let available = match label.0 {
+            CardCommand::Train(unit) => resources >= economy_config.cost(unit),
+            CardCommand::Ability(index) => hero.is_some_and(|(abilities, mana)| {
+                abilities
+                    .0
+                    .get(index)
+                    .is_some_and(|ability| ability.is_ready() && mana.current >= ability.mana_cost)
+            }),
+            _ => true,
+        };
+
+        // Only written on change, so that text is not laid out again every frame
+        color.set_if_neq(TextColor(if available {
+            Color::WHITE
+        } else {
+            UNAVAILABLE_TEXT
+        }));
+    }
+}
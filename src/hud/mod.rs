@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_states::GameState,
+    hud::{
+        command_card::{CommandCard, CommandCardPlugin},
+        overlays::OverlaysPlugin,
+        selection_panel::{SelectionPanel, SelectionPanelPlugin},
+    },
+    players::{LocalPlayer, Owner, Player},
+    units::{Selected, Unit},
+};
+
+pub mod command_card;
+pub mod overlays;
+pub mod selection_panel;
+
+/// Height of the bottom bar holding the selection panel and the command card.
+const HUD_HEIGHT: f32 = 170.;
+
+const HUD_BACKGROUND: Color = Color::srgba(0.05, 0.05, 0.05, 0.85);
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SelectionChanged>()
+            .add_plugins((SelectionPanelPlugin, CommandCardPlugin, OverlaysPlugin))
+            .add_systems(OnEnter(GameState::Playing), setup)
+            .add_systems(
+                Update,
+                (
+                    detect_selection_change.in_set(HudSet::DetectChanges),
+                    update_resource_bar,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .configure_sets(Update, HudSet::DetectChanges.before(HudSet::Rebuild));
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+enum HudSet {
+    DetectChanges,
+    /// Systems rebuilding part of the HUD on [`SelectionChanged`]
+    Rebuild,
+}
+
+/// Sent when units were selected or deselected, or the HUD was just spawned and has to be filled.
+#[derive(Event, Debug)]
+struct SelectionChanged;
+
+#[derive(Component)]
+struct ResourceBar;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Name::new("ResourceBar"),
+        StateScoped(GameState::Playing),
+        ResourceBar,
+        Text::default(),
+        TextFont {
+            font_size: 22.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        Name::new("Hud"),
+        StateScoped(GameState::Playing),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(0.),
+            width: Val::Percent(100.),
+            height: Val::Px(HUD_HEIGHT),
+            padding: UiRect::all(Val::Px(10.)),
+            column_gap: Val::Px(10.),
+            ..default()
+        },
+        BackgroundColor(HUD_BACKGROUND),
+        children![
+            (
+                Name::new("SelectionPanel"),
+                SelectionPanel,
+                Node {
+                    flex_grow: 1.,
+                    flex_wrap: FlexWrap::Wrap,
+                    align_content: AlignContent::FlexStart,
+                    column_gap: Val::Px(4.),
+                    row_gap: Val::Px(4.),
+                    ..default()
+                },
+            ),
+            (
+                Name::new("CommandCard"),
+                CommandCard,
+                Node {
+                    width: Val::Px(360.),
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::flex(4, 1.),
+                    grid_auto_rows: vec![GridTrack::px(70.)],
+                    column_gap: Val::Px(4.),
+                    row_gap: Val::Px(4.),
+                    ..default()
+                },
+            ),
+        ],
+    ));
+}
+
+fn detect_selection_change(
+    added_query: Query<(), Added<Selected>>,
+    mut removed: RemovedComponents<Selected>,
+    new_hud_query: Query<(), Added<CommandCard>>,
+    mut changes: EventWriter<SelectionChanged>,
+) {
+    // Every removal has to be read, or it would be seen again next frame
+    let removed = removed.read().count();
+    if removed > 0 || !added_query.is_empty() || !new_hud_query.is_empty() {
+        changes.write(SelectionChanged);
+    }
+}
+
+fn update_resource_bar(
+    mut bar_query: Query<&mut Text, With<ResourceBar>>,
+    players_query: Query<&Player>,
+    units_query: Query<&Owner, With<Unit>>,
+    local_player: Res<LocalPlayer>,
+) {
+    let Some(player) = players_query
+        .iter()
+        .find(|player| player.id == local_player.0)
+    else {
+        return;
+    };
+    let units = units_query
+        .iter()
+        .filter(|owner| owner.0 == local_player.0)
+        .count();
+
+    for mut text in bar_query.iter_mut() {
+        text.0 = format!("Resources: {}    Units: {units}", player.resources);
+    }
+}
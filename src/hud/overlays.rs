@@ -0,0 +1,242 @@
+use bevy::{
+    pbr::{DistanceFog, FogFalloff},
+    picking::hover::HoverMap,
+    prelude::*,
+};
+
+use crate::{
+    camera::MainCamera,
+    config::{
+        hero::HeroConfig,
+        settings::{BarVisibility, Settings},
+    },
+    game_states::GameState,
+    units::{
+        Selected, Unit,
+        health::Health,
+        hero::{Abilities, AbilityEffect, AbilityTarget, Hero, Mana},
+        tactics::Stance,
+    },
+};
+
+/// Height of the health bar above a unit's origin.
+const BAR_OFFSET: f32 = 0.55;
+const BAR_WIDTH: f32 = 0.4;
+/// Space between two bars stacked under the health bar.
+const BAR_SPACING: f32 = 0.04;
+const STATUS_RADIUS: f32 = 0.025;
+
+const BAR_BACKGROUND: Color = Color::srgb(0.1, 0.1, 0.1);
+const MANA_COLOR: Color = Color::srgb_u8(50, 110, 230);
+const EXPERIENCE_COLOR: Color = Color::srgb_u8(170, 90, 220);
+
+pub struct OverlaysPlugin;
+
+impl Plugin for OverlaysPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_gizmo_config(
+            OverlayGizmos,
+            GizmoConfig {
+                line: GizmoLineConfig {
+                    width: 4.,
+                    ..default()
+                },
+                // Drawn over the units they belong to
+                depth_bias: -1.,
+                ..default()
+            },
+        )
+        .add_systems(Update, draw_overlays.run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Bars and status icons are immediate mode lines, so that hundreds of units cost a few vertices
+/// each instead of a UI node per bar.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct OverlayGizmos;
+
+/// Something worth knowing about a unit at a glance, drawn as a coloured ring above its bars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// A passive ability is healing it
+    Regenerating,
+    /// Not enough mana left for any of its abilities
+    Drained,
+    HoldingGround,
+    Passive,
+}
+
+impl Status {
+    fn color(&self) -> Color {
+        match self {
+            Status::Regenerating => Color::srgb_u8(60, 220, 90),
+            Status::Drained => Color::srgb_u8(240, 140, 30),
+            Status::HoldingGround => Color::srgb_u8(80, 160, 255),
+            Status::Passive => Color::srgb_u8(200, 200, 200),
+        }
+    }
+}
+
+fn statuses(
+    health: &Health,
+    mana: Option<&Mana>,
+    abilities: Option<&Abilities>,
+    stance: Option<&Stance>,
+) -> Vec<Status> {
+    let mut statuses = Vec::new();
+
+    if let Some(abilities) = abilities {
+        let regenerates = abilities.0.iter().any(|ability| {
+            ability.target == AbilityTarget::Passive
+                && matches!(ability.effect, AbilityEffect::Heal(_))
+        });
+        if regenerates && health.current < health.max {
+            statuses.push(Status::Regenerating);
+        }
+
+        let costs: Vec<f32> = abilities
+            .0
+            .iter()
+            .filter(|ability| ability.target != AbilityTarget::Passive)
+            .map(|ability| ability.mana_cost)
+            .collect();
+        let current_mana = mana.map_or(0., |mana| mana.current);
+        if !costs.is_empty() && costs.iter().all(|cost| current_mana < *cost) {
+            statuses.push(Status::Drained);
+        }
+    }
+
+    match stance {
+        Some(Stance::HoldGround) => statuses.push(Status::HoldingGround),
+        Some(Stance::Passive) => statuses.push(Status::Passive),
+        _ => (),
+    }
+
+    statuses
+}
+
+/// Green when healthy, turning yellow then red as the unit gets hurt.
+fn health_color(fraction: f32) -> Color {
+    if fraction > 0.5 {
+        Color::srgb_u8(40, 200, 40)
+    } else if fraction > 0.25 {
+        Color::srgb_u8(230, 200, 30)
+    } else {
+        Color::srgb_u8(220, 40, 40)
+    }
+}
+
+/// Draws a horizontal bar facing the camera, filled up to `fraction`.
+fn draw_bar(
+    gizmos: &mut Gizmos<OverlayGizmos>,
+    center: Vec3,
+    right: Vec3,
+    fraction: f32,
+    color: Color,
+) {
+    let start = center - right * BAR_WIDTH / 2.;
+    let end = center + right * BAR_WIDTH / 2.;
+
+    gizmos.line(start, end, BAR_BACKGROUND);
+    gizmos.line(start, start.lerp(end, fraction.clamp(0., 1.)), color);
+}
+
+// Units off screen or lost in the fog of the weather have no overlay, as gizmos are drawn through
+// the fog. There is no fog of war yet, so every other unit in view gets one.
+fn draw_overlays(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    camera: Single<(&GlobalTransform, Option<&DistanceFog>), With<MainCamera>>,
+    units_query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ViewVisibility,
+            &Health,
+            Option<&Mana>,
+            Option<&Hero>,
+            Option<&Abilities>,
+            Option<&Stance>,
+            Has<Selected>,
+        ),
+        With<Unit>,
+    >,
+    hover_map: Res<HoverMap>,
+    settings: Res<Settings>,
+    hero_config: Res<HeroConfig>,
+) {
+    let (camera, fog) = camera.into_inner();
+    let fog_end = fog.and_then(|fog| match fog.falloff {
+        FogFalloff::Linear { end, .. } => Some(end),
+        _ => None,
+    });
+    let right = camera.right().as_vec3();
+    let up = camera.up().as_vec3();
+    let facing = camera.rotation();
+
+    let visibility = settings.interface.unit_bars;
+    let is_hovered = |unit: Entity| hover_map.values().any(|hits| hits.contains_key(&unit));
+
+    for (unit, transform, view_visibility, health, mana, hero, abilities, stance, selected) in
+        units_query.iter()
+    {
+        if !view_visibility.get() {
+            continue;
+        }
+        if fog_end.is_some_and(|end| camera.translation().distance(transform.translation()) > end) {
+            continue;
+        }
+        let shown = match visibility {
+            BarVisibility::Always => true,
+            BarVisibility::Hovered => selected || is_hovered(unit),
+            BarVisibility::Selected => selected,
+        };
+        if !shown {
+            continue;
+        }
+
+        let mut center = transform.translation() + Vec3::Y * BAR_OFFSET;
+        let health_fraction = health.current / health.max;
+        draw_bar(
+            &mut gizmos,
+            center,
+            right,
+            health_fraction,
+            health_color(health_fraction),
+        );
+
+        if let Some(mana) = mana.filter(|mana| mana.max > 0.) {
+            center -= up * BAR_SPACING;
+            draw_bar(
+                &mut gizmos,
+                center,
+                right,
+                mana.current / mana.max,
+                MANA_COLOR,
+            );
+        }
+
+        // Progress towards the next level, full once the last one is reached
+        if let Some(hero) = hero {
+            let progress = if hero.level >= hero_config.max_level {
+                1.
+            } else {
+                hero.experience as f32 / hero_config.experience_to_level_up(hero.level) as f32
+            };
+            center -= up * BAR_SPACING;
+            draw_bar(&mut gizmos, center, right, progress, EXPERIENCE_COLOR);
+        }
+
+        let unit_statuses = statuses(health, mana, abilities, stance);
+        let first = transform.translation() + Vec3::Y * BAR_OFFSET + up * 2. * BAR_SPACING
+            - right * BAR_WIDTH / 2.
+            + right * STATUS_RADIUS;
+        for (index, status) in unit_statuses.into_iter().enumerate() {
+            let position = first + right * index as f32 * 3. * STATUS_RADIUS;
+            gizmos.circle(
+                Isometry3d::new(position, facing),
+                STATUS_RADIUS,
+                status.color(),
+            );
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_states::GameState,
+    hud::{HudSet, SelectionChanged},
+    orders::UnitId,
+    players::{Owner, Player},
+    units::{
+        Selected, Unit, UnitSelector,
+        combat::Weapon,
+        health::Health,
+        hero::{Hero, Mana},
+        tactics::Stance,
+        utils::{add_selection, remove_selection},
+    },
+};
+
+/// Icons shown for a group, the rest of the selection is only counted.
+const MAX_ICONS: usize = 24;
+
+const ICON_SIZE: f32 = 44.;
+const PORTRAIT_SIZE: f32 = 120.;
+
+const HEALTH_COLOR: Color = Color::srgb_u8(40, 200, 40);
+
+pub struct SelectionPanelPlugin;
+
+impl Plugin for SelectionPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                rebuild
+                    .in_set(HudSet::Rebuild)
+                    .run_if(on_event::<SelectionChanged>),
+                (update_unit_stats, update_icon_health).after(HudSet::Rebuild),
+            )
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Shows the stats of a single selected unit, or an icon per unit for groups.
+#[derive(Component)]
+pub struct SelectionPanel;
+
+#[derive(Component)]
+struct UnitStats(Entity);
+
+#[derive(Component)]
+struct IconHealth(Entity);
+
+fn rebuild(
+    mut commands: Commands,
+    panel: Single<Entity, With<SelectionPanel>>,
+    selected_query: Query<(Entity, &UnitId, &Owner, Has<Hero>), (With<Selected>, With<Unit>)>,
+    players_query: Query<&Player>,
+) {
+    let panel = panel.into_inner();
+    commands.entity(panel).despawn_related::<Children>();
+
+    // Heroes first, then in the order units were created
+    let mut selected: Vec<_> = selected_query.iter().collect();
+    selected.sort_by_key(|(_, id, _, is_hero)| (!is_hero, **id));
+
+    let color_of = |owner: &Owner| {
+        players_query
+            .iter()
+            .find(|player| player.id == owner.0)
+            .map_or(Color::WHITE, |player| player.color.color())
+    };
+
+    match selected.as_slice() {
+        [] => (),
+        [(unit, _, owner, is_hero)] => {
+            commands.entity(panel).with_children(|parent| {
+                parent.spawn(portrait(*is_hero, color_of(owner), PORTRAIT_SIZE));
+                parent.spawn((
+                    UnitStats(*unit),
+                    Text::default(),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    Node {
+                        margin: UiRect::left(Val::Px(10.)),
+                        ..default()
+                    },
+                ));
+            });
+        }
+        group => {
+            commands.entity(panel).with_children(|parent| {
+                for (unit, _, owner, is_hero) in group.iter().take(MAX_ICONS) {
+                    let unit = *unit;
+                    parent
+                        .spawn((Button, portrait(*is_hero, color_of(owner), ICON_SIZE)))
+                        .with_child((
+                            IconHealth(unit),
+                            Node {
+                                position_type: PositionType::Absolute,
+                                bottom: Val::Px(0.),
+                                left: Val::Px(0.),
+                                height: Val::Px(4.),
+                                width: Val::Percent(100.),
+                                ..default()
+                            },
+                            BackgroundColor(HEALTH_COLOR),
+                        ))
+                        .observe(move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                            commands.run_system_cached_with(subselect, unit);
+                        });
+                }
+
+                if group.len() > MAX_ICONS {
+                    parent.spawn((
+                        Text::new(format!("+{}", group.len() - MAX_ICONS)),
+                        TextFont {
+                            font_size: 22.0,
+                            ..default()
+                        },
+                    ));
+                }
+            });
+        }
+    }
+}
+
+/// A square in the owner's colour with the initial of the kind of unit.
+fn portrait(is_hero: bool, color: Color, size: f32) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(size),
+            height: Val::Px(size),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(color),
+        children![(
+            Text::new(if is_hero { "H" } else { "S" }),
+            TextFont {
+                font_size: size / 2.,
+                ..default()
+            },
+        )],
+    )
+}
+
+/// Clicking an icon of a group keeps that unit selected alone.
+fn subselect(
+    In(unit): In<Entity>,
+    mut commands: Commands,
+    units_query: Query<Option<&Children>, With<Unit>>,
+    unit_selectors: Query<(), With<UnitSelector>>,
+    selected_units: Query<Entity, (With<Selected>, Without<UnitSelector>)>,
+    unit_selectors_selected: Query<Entity, (With<UnitSelector>, With<Selected>)>,
+) {
+    // The unit may have died since the icon was drawn
+    let Ok(children) = units_query.get(unit) else {
+        return;
+    };
+
+    remove_selection(&mut commands, selected_units, unit_selectors_selected);
+    add_selection(&mut commands, unit, children, &unit_selectors);
+}
+
+fn update_unit_stats(
+    mut stats_query: Query<(&mut Text, &UnitStats)>,
+    units_query: Query<(
+        &Health,
+        Option<&Weapon>,
+        Option<&Stance>,
+        Option<&Mana>,
+        Option<&Hero>,
+    )>,
+) {
+    for (mut text, stats) in stats_query.iter_mut() {
+        let Ok((health, weapon, stance, mana, hero)) = units_query.get(stats.0) else {
+            continue;
+        };
+
+        let mut lines = vec![match hero {
+            Some(hero) => format!("Hero, level {} ({} xp)", hero.level, hero.experience),
+            None => "Soldier".to_string(),
+        }];
+        lines.push(format!(
+            "Health {:.0}/{:.0}",
+            health.current.max(0.),
+            health.max
+        ));
+        if let Some(mana) = mana {
+            lines.push(format!("Mana {:.0}/{:.0}", mana.current, mana.max));
+        }
+        if let Some(weapon) = weapon {
+            lines.push(format!(
+                "Damage {:.0}, range {:.1}",
+                weapon.damage, weapon.range
+            ));
+        }
+        if let Some(stance) = stance {
+            lines.push(format!("Stance {stance:?}"));
+        }
+
+        text.0 = lines.join("\n");
+    }
+}
+
+fn update_icon_health(
+    mut icons_query: Query<(&mut Node, &IconHealth)>,
+    health_query: Query<&Health>,
+) {
+    for (mut node, icon) in icons_query.iter_mut() {
+        if let Ok(health) = health_query.get(icon.0) {
+            node.width = Val::Percent(100. * (health.current / health.max).clamp(0., 1.));
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::{collections::BTreeMap, fmt};
+
+use bevy::{input::InputSystem, prelude::*};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::config::settings::Settings;
+
+pub struct InputActionsPlugin;
+
+impl Plugin for InputActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Action>()
+            .init_resource::<ButtonInput<Action>>()
+            .add_systems(PreUpdate, update_actions.after(InputSystem));
+    }
+}
+
+/// Everything the player can do with the keyboard and mouse. Gameplay code reads
+/// `Res<ButtonInput<Action>>` instead of raw key codes so that every action can be rebound.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Reflect,
+    Serialize,
+    Deserialize,
+    EnumIter,
+)]
+pub enum Action {
+    CameraPanUp,
+    CameraPanDown,
+    CameraPanLeft,
+    CameraPanRight,
+    Select,
+    Command,
+    Move,
+    Stop,
+    Attack,
+    Ability1,
+    Ability2,
+    Ability3,
+    Ability4,
+    CancelAbility,
+    TrainSoldier,
+    CycleStance,
+    QuickSave,
+    ReplayPause,
+    ReplaySlower,
+    ReplayFaster,
+    ExitGame,
+}
+
+impl Action {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::CameraPanUp => "Camera up",
+            Action::CameraPanDown => "Camera down",
+            Action::CameraPanLeft => "Camera left",
+            Action::CameraPanRight => "Camera right",
+            Action::Select => "Select",
+            Action::Command => "Command",
+            Action::Move => "Move",
+            Action::Stop => "Stop",
+            Action::Attack => "Attack",
+            Action::Ability1 => "Ability 1",
+            Action::Ability2 => "Ability 2",
+            Action::Ability3 => "Ability 3",
+            Action::Ability4 => "Ability 4",
+            Action::CancelAbility => "Cancel ability",
+            Action::TrainSoldier => "Train soldier",
+            Action::CycleStance => "Cycle stance",
+            Action::QuickSave => "Quick save",
+            Action::ReplayPause => "Pause replay",
+            Action::ReplaySlower => "Slower replay",
+            Action::ReplayFaster => "Faster replay",
+            Action::ExitGame => "Exit game",
+        }
+    }
+
+    pub fn default_chord(&self) -> Chord {
+        use InputButton::{Key, Mouse};
+
+        Chord(match self {
+            Action::CameraPanUp => vec![Key(KeyCode::ArrowUp)],
+            Action::CameraPanDown => vec![Key(KeyCode::ArrowDown)],
+            Action::CameraPanLeft => vec![Key(KeyCode::ArrowLeft)],
+            Action::CameraPanRight => vec![Key(KeyCode::ArrowRight)],
+            Action::Select => vec![Mouse(MouseButton::Left)],
+            Action::Command => vec![Mouse(MouseButton::Right)],
+            Action::Move => vec![Key(KeyCode::KeyM)],
+            Action::Stop => vec![Key(KeyCode::KeyS)],
+            Action::Attack => vec![Key(KeyCode::KeyA)],
+            Action::Ability1 => vec![Key(KeyCode::KeyQ)],
+            Action::Ability2 => vec![Key(KeyCode::KeyW)],
+            Action::Ability3 => vec![Key(KeyCode::KeyE)],
+            Action::Ability4 => vec![Key(KeyCode::KeyR)],
+            Action::CancelAbility => vec![Key(KeyCode::Escape)],
+            Action::TrainSoldier => vec![Key(KeyCode::KeyT)],
+            Action::CycleStance => vec![Key(KeyCode::KeyG)],
+            Action::QuickSave => vec![Key(KeyCode::F5)],
+            Action::ReplayPause => vec![Key(KeyCode::Space)],
+            Action::ReplaySlower => vec![Key(KeyCode::Minus)],
+            Action::ReplayFaster => vec![Key(KeyCode::Equal)],
+            Action::ExitGame => vec![Key(KeyCode::SuperLeft), Key(KeyCode::Escape)],
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum InputButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl fmt::Display for InputButton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputButton::Key(key) => write!(f, "{key:?}"),
+            InputButton::Mouse(button) => write!(f, "Mouse{button:?}"),
+        }
+    }
+}
+
+/// Buttons that must all be held down for an action to be pressed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub struct Chord(pub Vec<InputButton>);
+
+impl Chord {
+    fn pressed(&self, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        !self.0.is_empty()
+            && self.0.iter().all(|button| match button {
+                InputButton::Key(key) => keys.pressed(*key),
+                InputButton::Mouse(mouse_button) => mouse.pressed(*mouse_button),
+            })
+    }
+
+    /// Whether `other` holds every button of this chord and more.
+    fn is_part_of(&self, other: &Chord) -> bool {
+        self.0.len() < other.0.len() && self.0.iter().all(|button| other.0.contains(button))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let buttons: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", buttons.join(" + "))
+    }
+}
+
+/// Actions triggered by clicking units or the terrain, which must be bound to a single mouse
+/// button.
+const POINTER_ACTIONS: [Action; 2] = [Action::Select, Action::Command];
+
+/// Rebound actions, stored in the settings file. Actions missing from the map use their
+/// [`Action::default_chord`].
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct InputBindings {
+    pub bindings: BTreeMap<Action, Chord>,
+}
+
+impl InputBindings {
+    pub fn chord(&self, action: Action) -> Chord {
+        self.bindings
+            .get(&action)
+            .cloned()
+            .unwrap_or_else(|| action.default_chord())
+    }
+
+    /// Why `chord` can't be bound to `action`, if it can't.
+    pub fn check(&self, action: Action, chord: &Chord) -> Result<(), BindingError> {
+        // Click observers only know the button that was clicked, see `pointer_action`
+        if POINTER_ACTIONS.contains(&action) && !matches!(chord.0[..], [InputButton::Mouse(_)]) {
+            return Err(BindingError::NotAMouseButton);
+        }
+
+        match Action::iter().find(|other| *other != action && self.chord(*other) == *chord) {
+            Some(other) => Err(BindingError::UsedBy(other)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn rebind(&mut self, action: Action, chord: Chord) {
+        if chord == action.default_chord() {
+            self.bindings.remove(&action);
+        } else {
+            self.bindings.insert(action, chord);
+        }
+    }
+
+    /// The pointer actions bound to a single mouse button, used by click observers which only
+    /// know which [`PointerButton`] triggered them.
+    pub fn pointer_action(&self, button: PointerButton) -> Option<Action> {
+        let mouse_button = match button {
+            PointerButton::Primary => MouseButton::Left,
+            PointerButton::Secondary => MouseButton::Right,
+            PointerButton::Middle => MouseButton::Middle,
+        };
+
+        POINTER_ACTIONS
+            .into_iter()
+            .find(|action| self.chord(*action).0 == [InputButton::Mouse(mouse_button)])
+    }
+}
+
+/// A chord refused by [`InputBindings::check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingError {
+    /// Another action is already bound to it
+    UsedBy(Action),
+    /// Pointer actions can only be bound to a single mouse button
+    NotAMouseButton,
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingError::UsedBy(action) => write!(f, "Used by {}", action.label()),
+            BindingError::NotAMouseButton => write!(f, "Needs a mouse button"),
+        }
+    }
+}
+
+fn update_actions(
+    mut actions: ResMut<ButtonInput<Action>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    settings: Res<Settings>,
+) {
+    // Clearing only resets the `just_*` sets, `press` and `release` then only flag the actions
+    // whose state actually changed since last frame.
+    actions.clear();
+
+    let held: Vec<(Action, Chord)> = Action::iter()
+        .map(|action| (action, settings.input.chord(action)))
+        .filter(|(_, chord)| chord.pressed(&keys, &mouse))
+        .collect();
+
+    for action in Action::iter() {
+        // The longest chord held wins, so that Super + Escape doesn't also press Escape's action
+        let pressed = held.iter().any(|(held_action, chord)| {
+            *held_action == action && !held.iter().any(|(_, other)| chord.is_part_of(other))
+        });
+        if pressed {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+}
@@ -1,7 +1,133 @@
+use bevy::{app::PluginGroupBuilder, prelude::*};
+
+use crate::{
+    ai::AiPlugin,
+    buildings::BuildingsPlugin,
+    camera::CameraPlugin,
+    config::ConfigPlugin,
+    economy::EconomyPlugin,
+    game_states::{GameStatePlugin, LoadingPlugin},
+    hover::HoverPlugin,
+    hud::HudPlugin,
+    input::InputActionsPlugin,
+    light::LightPlugin,
+    match_settings::MatchSettingsPlugin,
+    menus::MenusPlugin,
+    network::NetworkPlugin,
+    order_feedback::OrderFeedbackPlugin,
+    orders::OrdersPlugin,
+    picking::PickingBackendPlugin,
+    players::PlayersPlugin,
+    replay::ReplayPlugin,
+    save::SavePlugin,
+    simulation::SimulationPlugin,
+    terrain::TerrainPlugin,
+    units::{UnitsPlugin, animation::UnitAnimationPlugin, rendering::UnitRenderingPlugin},
+    victory::VictoryPlugin,
+    weather::{WeatherPlugin, effects::WeatherEffectsPlugin},
+};
+
+pub mod ai;
+pub mod buildings;
 pub mod camera;
+pub mod cli;
 pub mod config;
+pub mod economy;
 pub mod game_states;
+pub mod headless;
+pub mod hover;
+pub mod hud;
+pub mod input;
 pub mod light;
+pub mod match_settings;
 pub mod menus;
+pub mod network;
+pub mod order_feedback;
+pub mod orders;
+pub mod paths;
+pub mod picking;
+pub mod players;
+pub mod replay;
+pub mod save;
+pub mod simulation;
+pub mod stress;
 pub mod terrain;
 pub mod units;
+pub mod victory;
+pub mod weather;
+
+/// Plugins playing the match itself. They don't need a window or a renderer, so they are shared
+/// by the game and [`headless`] runs. [`game_states::GameStatePlugin`] must be added before them.
+pub struct GameplayPlugins;
+
+impl PluginGroup for GameplayPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(ConfigPlugin)
+            .add(InputActionsPlugin)
+            .add(SimulationPlugin)
+            .add(OrdersPlugin)
+            .add(MatchSettingsPlugin)
+            .add(PlayersPlugin)
+            .add(TerrainPlugin)
+            .add(UnitsPlugin)
+            .add(BuildingsPlugin)
+            .add(EconomyPlugin)
+            .add(AiPlugin)
+            .add(VictoryPlugin)
+            .add(WeatherPlugin)
+            .add(ReplayPlugin)
+            .add(SavePlugin)
+    }
+}
+
+/// The whole game in a window. Debugging tools are only added with the `dev`, `inspector` and
+/// `picking-debug` features. Other binaries can leave plugins out, e.g.
+/// `GamePlugins.build().disable::<MenusPlugin>()`.
+pub struct GamePlugins;
+
+impl PluginGroup for GamePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        // Stays as is when every debugging feature is off
+        #[allow(unused_mut)]
+        let mut group = PluginGroupBuilder::start::<Self>()
+            .add_group(DefaultPlugins.set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "RTS".into(),
+                    fit_canvas_to_parent: true,
+                    ..default()
+                }),
+                ..default()
+            }))
+            .add(PickingBackendPlugin)
+            .add(GameStatePlugin)
+            .add(LoadingPlugin::default())
+            .add_group(GameplayPlugins)
+            .add(NetworkPlugin)
+            .add(MenusPlugin)
+            .add(HudPlugin)
+            .add(HoverPlugin)
+            .add(OrderFeedbackPlugin)
+            .add(UnitRenderingPlugin)
+            .add(UnitAnimationPlugin)
+            .add(CameraPlugin)
+            .add(LightPlugin)
+            .add(WeatherEffectsPlugin);
+
+        #[cfg(feature = "dev")]
+        {
+            group = group.add(bevy::diagnostic::LogDiagnosticsPlugin::default());
+        }
+
+        #[cfg(feature = "inspector")]
+        {
+            group = group
+                .add(bevy_inspector_egui::bevy_egui::EguiPlugin {
+                    enable_multipass_for_primary_context: true,
+                })
+                .add(bevy_inspector_egui::quick::WorldInspectorPlugin::new());
+        }
+
+        group
+    }
+}
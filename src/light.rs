@@ -1,28 +1,149 @@
-use bevy::{pbr::CascadeShadowConfigBuilder, prelude::*};
+use std::f32::consts::TAU;
 
-use crate::game_states::GameState;
+use bevy::{
+    pbr::{CascadeShadowConfig, CascadeShadowConfigBuilder, DirectionalLightShadowMap},
+    prelude::*,
+};
+
+use crate::{
+    config::{
+        lighting::LightingConfig,
+        settings::{Settings, ShadowQuality},
+    },
+    game_states::GameState,
+    simulation::SimulationTick,
+};
+
+/// Distance of the sun from the centre of the map, only its direction matters.
+const SUN_DISTANCE: f32 = 10.;
 
 pub struct LightPlugin;
 
 impl Plugin for LightPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Playing), setup);
+        app.add_systems(OnEnter(GameState::Playing), setup)
+            .add_systems(
+                Update,
+                (
+                    apply_shadow_quality.run_if(resource_changed::<Settings>),
+                    update_sun.run_if(in_state(GameState::Playing)),
+                ),
+            );
+    }
+}
+
+/// The directional light following the time of day, the sun by day and the moon by night.
+#[derive(Component)]
+struct Sun;
+
+/// `distance_scale` stretches the cascades for the long shadows of a low sun.
+fn cascade_shadow_config(quality: ShadowQuality, distance_scale: f32) -> CascadeShadowConfig {
+    let (num_cascades, maximum_distance) = match quality {
+        ShadowQuality::Off | ShadowQuality::Low => (2, 15.),
+        ShadowQuality::Medium => (4, 25.),
+        ShadowQuality::High => (4, 40.),
+    };
+
+    CascadeShadowConfigBuilder {
+        num_cascades,
+        first_cascade_far_bound: 7.0 * distance_scale,
+        maximum_distance: maximum_distance * distance_scale,
+        ..default()
+    }
+    .build()
+}
+
+fn shadow_map_size(quality: ShadowQuality) -> usize {
+    match quality {
+        ShadowQuality::Off | ShadowQuality::Low => 1024,
+        ShadowQuality::Medium => 2048,
+        ShadowQuality::High => 4096,
     }
 }
 
-fn setup(mut commands: Commands) {
+fn setup(mut commands: Commands, settings: Res<Settings>) {
+    let quality = settings.graphics.shadow_quality;
+
+    // Placed by `update_sun` on the first frame
     commands.spawn((
+        Name::new("Sun"),
+        Sun,
+        StateScoped(GameState::Playing),
         DirectionalLight {
-            illuminance: light_consts::lux::FULL_DAYLIGHT,
-            shadows_enabled: true,
+            shadows_enabled: quality != ShadowQuality::Off,
             ..default()
         },
-        Transform::from_translation(Vec3::new(0.0, 2.0, 2.0)).looking_at(Vec3::ZERO, Vec3::Y),
-        CascadeShadowConfigBuilder {
-            first_cascade_far_bound: 7.0,
-            maximum_distance: 25.,
-            ..default()
-        }
-        .build(),
+        cascade_shadow_config(quality, 1.),
     ));
 }
+
+fn apply_shadow_quality(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(DirectionalLightShadowMap {
+        size: shadow_map_size(settings.graphics.shadow_quality),
+    });
+}
+
+/// Moves the sun across the sky from sunrise at a quarter of the day to sunset at three quarters,
+/// the moon takes over at night.
+fn update_sun(
+    mut commands: Commands,
+    mut sun_query: Query<(Entity, &mut DirectionalLight, &mut Transform), With<Sun>>,
+    new_sun_query: Query<(), Added<Sun>>,
+    mut ambient_light: ResMut<AmbientLight>,
+    lighting_config: Res<LightingConfig>,
+    settings: Res<Settings>,
+    tick: Res<SimulationTick>,
+    // Cascades are only rebuilt when the quality or the stretch step changes
+    mut cascades: Local<Option<(ShadowQuality, u32)>>,
+) {
+    let config = &*lighting_config;
+    let angle = (config.time_of_day(tick.0) - 0.25) * TAU;
+    let height = angle.sin();
+    let sun_position = Vec3::new(angle.cos(), height, 0.4).normalize() * SUN_DISTANCE;
+
+    // Highest at noon for the sun and at midnight for the moon
+    let elevation = height.abs();
+    let twilight = (elevation * 3.).min(1.);
+    let (position, illuminance, color) = if height >= 0. {
+        (
+            sun_position,
+            config
+                .night_illuminance
+                .lerp(config.noon_illuminance, elevation),
+            config.dusk_color.mix(&config.day_color, twilight),
+        )
+    } else {
+        (
+            -sun_position,
+            config.night_illuminance,
+            config.dusk_color.mix(&config.night_color, twilight),
+        )
+    };
+    let daylight = height.max(0.);
+
+    ambient_light.brightness = config
+        .night_ambient_brightness
+        .lerp(config.day_ambient_brightness, daylight);
+    ambient_light.color = config
+        .night_color
+        .mix(&config.day_color, twilight.max(daylight));
+
+    let quality = settings.graphics.shadow_quality;
+    let stretch_step = ((1. - elevation) * 10.).round() as u32;
+    let rebuild_cascades = !new_sun_query.is_empty() || *cascades != Some((quality, stretch_step));
+    *cascades = Some((quality, stretch_step));
+    let distance_scale = 1. + (config.dusk_shadow_distance - 1.) * stretch_step as f32 / 10.;
+
+    for (entity, mut light, mut transform) in sun_query.iter_mut() {
+        light.illuminance = illuminance;
+        light.color = color;
+        light.shadows_enabled = quality != ShadowQuality::Off;
+        *transform = Transform::from_translation(position).looking_at(Vec3::ZERO, Vec3::Y);
+
+        if rebuild_cascades {
+            commands
+                .entity(entity)
+                .insert(cascade_shadow_config(quality, distance_scale));
+        }
+    }
+}
@@ -1,59 +1,40 @@
+use std::env;
+
 use anyhow::Error;
-use bevy::{diagnostic::LogDiagnosticsPlugin, prelude::*};
-use bevy_asset_loader::loading_state::{LoadingState, LoadingStateAppExt};
-use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
-use bevy_rapier3d::{
-    plugin::{NoUserData, RapierPhysicsPlugin},
-    prelude::{RapierPickingPlugin, RapierPickingSettings},
-    render::RapierDebugRenderPlugin,
-};
+use bevy::prelude::*;
 use rts_game_rs::{
-    camera::CameraPlugin,
-    config::ConfigPlugin,
-    game_states::{GameState, GameStatePlugin},
-    light::LightPlugin,
-    menus::MenusPlugin,
-    terrain::TerrainPlugin,
-    units::UnitsPlugin,
+    GamePlugins,
+    cli::{LaunchOptions, LaunchPlugin, USAGE},
+    game_states::LoadingPlugin,
+    headless::run_scenario,
+    stress::StressTestPlugin,
 };
 
 fn main() -> Result<(), Error> {
-    App::new()
-        .add_plugins((
-            DefaultPlugins.set(WindowPlugin {
-                primary_window: Some(Window {
-                    title: "RTS".into(),
-                    fit_canvas_to_parent: true,
-                    ..default()
-                }),
-                ..default()
-            }),
-            LogDiagnosticsPlugin::default(),
-            EguiPlugin {
-                enable_multipass_for_primary_context: true,
-            },
-            WorldInspectorPlugin::new(),
-            RapierPhysicsPlugin::<NoUserData>::default(),
-            RapierDebugRenderPlugin::default(),
-            RapierPickingPlugin,
-        ))
-        .insert_resource(RapierPickingSettings {
-            require_markers: true,
-            ..default()
-        })
-        .add_plugins(GameStatePlugin)
-        .add_loading_state(
-            LoadingState::new(GameState::Loading).continue_to_state(GameState::StartMenu),
-        )
-        .add_plugins((
-            ConfigPlugin,
-            MenusPlugin,
-            CameraPlugin,
-            TerrainPlugin,
-            LightPlugin,
-            UnitsPlugin,
-        ))
-        .run();
+    let options = LaunchOptions::parse(env::args().skip(1))?;
+    if options.help {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    if options.headless {
+        let report = run_scenario(options.scenario(), options.ticks);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let mut app = App::new();
+    app.add_plugins(GamePlugins.set(LoadingPlugin {
+        next_state: options.state,
+    }))
+    .add_plugins(LaunchPlugin {
+        match_settings: options.match_settings,
+        replay: options.replay,
+    });
+    if let Some(units) = options.stress_units {
+        app.add_plugins(StressTestPlugin { units });
+    }
+    app.run();
 
     Ok(())
 }
@@ -0,0 +1,200 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::weather::{Weather, WeatherSchedule, WeatherTrigger};
+
+pub const MAX_PLAYERS: usize = 4;
+
+pub struct MatchSettingsPlugin;
+
+impl Plugin for MatchSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MatchSettings>()
+            .init_resource::<MatchSettings>();
+    }
+}
+
+/// Everything chosen in the game selection screen, read when entering [`crate::game_states::GameState::Playing`].
+#[derive(Resource, Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct MatchSettings {
+    pub map: MapDefinition,
+    pub slots: [PlayerSlot; MAX_PLAYERS],
+    pub starting_resources: u32,
+    pub victory_condition: VictoryCondition,
+    /// Seeds the [`crate::simulation::SimulationRng`], picked anew for every match
+    pub seed: u64,
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        let colors = [
+            PlayerColor::Blue,
+            PlayerColor::Red,
+            PlayerColor::Green,
+            PlayerColor::Yellow,
+        ];
+
+        Self {
+            map: MapDefinition::builtin().remove(0),
+            slots: std::array::from_fn(|index| PlayerSlot {
+                kind: match index {
+                    0 => SlotKind::Human,
+                    1 => SlotKind::Ai,
+                    _ => SlotKind::Closed,
+                },
+                team: index as u8 + 1,
+                color: colors[index],
+                difficulty: Difficulty::Normal,
+            }),
+            starting_resources: 1000,
+            victory_condition: VictoryCondition::EliminateAllUnits,
+            seed: 0,
+        }
+    }
+}
+
+impl MatchSettings {
+    /// Slots taking part in the match along with their index, which is also their player id.
+    pub fn open_slots(&self) -> impl Iterator<Item = (usize, &PlayerSlot)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.kind != SlotKind::Closed)
+    }
+}
+
+/// A seed for [`MatchSettings::seed`] that differs between matches.
+pub fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum SlotKind {
+    Human,
+    Ai,
+    Closed,
+}
+
+#[derive(Reflect, Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerSlot {
+    pub kind: SlotKind,
+    pub team: u8,
+    pub color: PlayerColor,
+    /// Only used by [`SlotKind::Ai`] slots
+    pub difficulty: Difficulty,
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum PlayerColor {
+    Blue,
+    Red,
+    Green,
+    Yellow,
+    Purple,
+    Orange,
+    Teal,
+    White,
+}
+
+impl PlayerColor {
+    pub fn color(&self) -> Color {
+        match self {
+            PlayerColor::Blue => Color::srgb_u8(50, 50, 200),
+            PlayerColor::Red => Color::srgb_u8(200, 40, 40),
+            PlayerColor::Green => Color::srgb_u8(40, 160, 40),
+            PlayerColor::Yellow => Color::srgb_u8(220, 200, 40),
+            PlayerColor::Purple => Color::srgb_u8(130, 50, 180),
+            PlayerColor::Orange => Color::srgb_u8(230, 120, 20),
+            PlayerColor::Teal => Color::srgb_u8(30, 160, 160),
+            PlayerColor::White => Color::srgb_u8(220, 220, 220),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum VictoryCondition {
+    EliminateAllUnits,
+    HoldPoint,
+    TimeLimit,
+    DestroyAllBuildings,
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapDefinition {
+    pub name: String,
+    /// Half of the width and depth of the terrain, centered on the origin
+    pub half_size: Vec2,
+    /// Where each player's units are spawned, indexed by player id
+    pub start_positions: [Vec2; MAX_PLAYERS],
+    /// Missing from maps made before weather existed, which get random weather
+    #[serde(default)]
+    pub weather: WeatherSchedule,
+}
+
+impl MapDefinition {
+    pub fn builtin() -> Vec<MapDefinition> {
+        vec![
+            MapDefinition {
+                name: "Plains".into(),
+                half_size: Vec2::new(5., 5.),
+                start_positions: [
+                    Vec2::new(-3., -3.),
+                    Vec2::new(3., 3.),
+                    Vec2::new(-3., 3.),
+                    Vec2::new(3., -3.),
+                ],
+                // Clear for the whole match, so that it plays the same in every season
+                weather: WeatherSchedule::Triggers(Vec::new()),
+            },
+            MapDefinition {
+                name: "Valley".into(),
+                half_size: Vec2::new(10., 6.),
+                start_positions: [
+                    Vec2::new(-8., 0.),
+                    Vec2::new(8., 0.),
+                    Vec2::new(0., -4.),
+                    Vec2::new(0., 4.),
+                ],
+                // Morning mist lifting, then rain late in the match
+                weather: WeatherSchedule::Triggers(vec![
+                    WeatherTrigger {
+                        at_seconds: 0.,
+                        weather: Weather::Fog,
+                    },
+                    WeatherTrigger {
+                        at_seconds: 180.,
+                        weather: Weather::Clear,
+                    },
+                    WeatherTrigger {
+                        at_seconds: 600.,
+                        weather: Weather::Rain,
+                    },
+                ]),
+            },
+            MapDefinition {
+                name: "Highlands".into(),
+                half_size: Vec2::new(15., 15.),
+                start_positions: [
+                    Vec2::new(-12., -12.),
+                    Vec2::new(12., 12.),
+                    Vec2::new(-12., 12.),
+                    Vec2::new(12., -12.),
+                ],
+                weather: WeatherSchedule::Random,
+            },
+        ]
+    }
+}
@@ -1,15 +1,247 @@
-use bevy::prelude::*;
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+use strum::IntoEnumIterator;
 
-use crate::game_states::GameState;
+use crate::{
+    config::network::{NetworkConfig, NetworkMode},
+    game_states::GameState,
+    match_settings::{
+        Difficulty, MAX_PLAYERS, MapDefinition, MatchSettings, PlayerColor, SlotKind,
+        VictoryCondition, random_seed,
+    },
+    menus::{arrow_button, cycle, menu_button, start_menu::get_state_transition_button},
+};
+
+const STARTING_RESOURCES: [u32; 4] = [500, 1000, 2000, 5000];
 
 pub struct GameSelectionPlugin;
 
 impl Plugin for GameSelectionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::GameSelection), setup);
+        app.add_systems(OnEnter(GameState::GameSelection), setup)
+            .add_systems(
+                Update,
+                update_field_values.run_if(
+                    in_state(GameState::GameSelection).and(resource_changed::<MatchSettings>),
+                ),
+            );
+    }
+}
+
+/// A value of the match being set up, shown with arrows to step through its choices.
+#[derive(Reflect, Debug, Clone, Copy)]
+enum MatchField {
+    Map,
+    StartingResources,
+    VictoryCondition,
+    SlotKind(usize),
+    SlotTeam(usize),
+    SlotColor(usize),
+    SlotDifficulty(usize),
+}
+
+/// Text showing the current value of a [`MatchField`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct MatchFieldValue(MatchField);
+
+impl MatchField {
+    fn value(&self, match_settings: &MatchSettings) -> String {
+        match self {
+            MatchField::Map => match_settings.map.name.clone(),
+            MatchField::StartingResources => match_settings.starting_resources.to_string(),
+            MatchField::VictoryCondition => format!("{:?}", match_settings.victory_condition),
+            MatchField::SlotKind(slot) => format!("{:?}", match_settings.slots[*slot].kind),
+            MatchField::SlotTeam(slot) => format!("Team {}", match_settings.slots[*slot].team),
+            MatchField::SlotColor(slot) => format!("{:?}", match_settings.slots[*slot].color),
+            MatchField::SlotDifficulty(slot) => match match_settings.slots[*slot].kind {
+                SlotKind::Ai => format!("{:?}", match_settings.slots[*slot].difficulty),
+                _ => "-".to_string(),
+            },
+        }
     }
+
+    fn adjust(&self, match_settings: &mut MatchSettings, step: i32) {
+        match self {
+            MatchField::Map => {
+                let maps = MapDefinition::builtin();
+                let index = maps
+                    .iter()
+                    .position(|map| map.name == match_settings.map.name)
+                    .unwrap_or(0) as i32;
+                match_settings.map =
+                    maps[(index + step).rem_euclid(maps.len() as i32) as usize].clone();
+            }
+            MatchField::StartingResources => {
+                match_settings.starting_resources =
+                    cycle(&STARTING_RESOURCES, match_settings.starting_resources, step);
+            }
+            MatchField::VictoryCondition => {
+                let conditions: Vec<VictoryCondition> = VictoryCondition::iter().collect();
+                match_settings.victory_condition =
+                    cycle(&conditions, match_settings.victory_condition, step);
+            }
+            MatchField::SlotKind(slot) => {
+                let kinds: Vec<SlotKind> = SlotKind::iter().collect();
+                let slot = &mut match_settings.slots[*slot];
+                slot.kind = cycle(&kinds, slot.kind, step);
+            }
+            MatchField::SlotTeam(slot) => {
+                let teams: Vec<u8> = (1..=MAX_PLAYERS as u8).collect();
+                let slot = &mut match_settings.slots[*slot];
+                slot.team = cycle(&teams, slot.team, step);
+            }
+            MatchField::SlotColor(slot) => {
+                let colors: Vec<PlayerColor> = PlayerColor::iter().collect();
+                let slot = &mut match_settings.slots[*slot];
+                slot.color = cycle(&colors, slot.color, step);
+            }
+            MatchField::SlotDifficulty(slot) => {
+                let difficulties: Vec<Difficulty> = Difficulty::iter().collect();
+                let slot = &mut match_settings.slots[*slot];
+                slot.difficulty = cycle(&difficulties, slot.difficulty, step);
+            }
+        }
+    }
+}
+
+fn setup(mut commands: Commands, match_settings: Res<MatchSettings>) {
+    let settings_fields: Vec<(&'static str, MatchField, String)> = [
+        ("Map", MatchField::Map),
+        ("Starting resources", MatchField::StartingResources),
+        ("Victory", MatchField::VictoryCondition),
+    ]
+    .into_iter()
+    .map(|(label, field)| (label, field, field.value(&match_settings)))
+    .collect();
+    let slot_fields: Vec<Vec<(MatchField, String)>> = (0..MAX_PLAYERS)
+        .map(|slot| {
+            [
+                MatchField::SlotKind(slot),
+                MatchField::SlotTeam(slot),
+                MatchField::SlotColor(slot),
+                MatchField::SlotDifficulty(slot),
+            ]
+            .into_iter()
+            .map(|field| (field, field.value(&match_settings)))
+            .collect()
+        })
+        .collect();
+
+    commands.spawn((
+        StateScoped(GameState::GameSelection),
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for (label, field, value) in settings_fields {
+                parent.spawn(field_row(label.to_string(), vec![(field, value)]));
+            }
+            for (slot, fields) in slot_fields.into_iter().enumerate() {
+                parent.spawn(field_row(format!("Player {}", slot + 1), fields));
+            }
+
+            parent
+                .spawn(Node {
+                    margin: UiRect::top(Val::Px(20.)),
+                    column_gap: Val::Px(10.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(get_state_transition_button("Back", GameState::StartMenu));
+                    parent
+                        .spawn((Name::new("StartButton"), menu_button("Start")))
+                        .observe(start_match);
+                });
+        })),
+    ));
 }
 
-fn setup(mut next_state_res: ResMut<NextState<GameState>>) {
+fn field_font() -> TextFont {
+    TextFont {
+        font_size: 26.0,
+        ..default()
+    }
+}
+
+fn field_row(label: String, fields: Vec<(MatchField, String)>) -> impl Bundle {
+    (
+        Name::new(format!("{label}Row")),
+        Node {
+            align_items: AlignItems::Center,
+            margin: UiRect::bottom(Val::Px(5.)),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Text::new(label),
+                field_font(),
+                Node {
+                    width: Val::Px(250.),
+                    ..default()
+                },
+            ));
+
+            for (field, value) in fields {
+                parent.spawn(arrow_button(
+                    "<",
+                    move |_: Trigger<Pointer<Click>>, mut match_settings: ResMut<MatchSettings>| {
+                        field.adjust(&mut match_settings, -1);
+                    },
+                ));
+                parent.spawn((
+                    MatchFieldValue(field),
+                    Text::new(value),
+                    field_font(),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    Node {
+                        width: Val::Px(150.),
+                        ..default()
+                    },
+                ));
+                parent.spawn(arrow_button(
+                    ">",
+                    move |_: Trigger<Pointer<Click>>, mut match_settings: ResMut<MatchSettings>| {
+                        field.adjust(&mut match_settings, 1);
+                    },
+                ));
+            }
+        })),
+    )
+}
+
+fn update_field_values(
+    match_settings: Res<MatchSettings>,
+    mut values_query: Query<(&mut Text, &MatchFieldValue)>,
+) {
+    for (mut text, MatchFieldValue(field)) in values_query.iter_mut() {
+        text.0 = field.value(&match_settings);
+    }
+}
+
+fn start_match(
+    _: Trigger<Pointer<Click>>,
+    mut match_settings: ResMut<MatchSettings>,
+    mut next_state_res: ResMut<NextState<GameState>>,
+    network_config: Res<NetworkConfig>,
+) {
+    // A match needs someone to play it on this machine
+    if !match_settings
+        .open_slots()
+        .any(|(_, slot)| slot.kind == SlotKind::Human)
+    {
+        warn!("At least one player slot must be Human to start a match");
+        return;
+    }
+
+    match_settings.seed = match network_config.mode {
+        NetworkMode::Udp { seed, .. } => seed,
+        _ => random_seed(),
+    };
+
     next_state_res.set(GameState::Playing);
 }
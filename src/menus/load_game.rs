@@ -0,0 +1,64 @@
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+
+use crate::{
+    game_states::GameState,
+    menus::{menu_button, start_menu::get_state_transition_button},
+    save::{LoadedSave, list_saves},
+};
+
+/// Only the most recent saves are listed, older ones are still on disk.
+const MAX_LISTED_SAVES: usize = 8;
+
+pub struct LoadGamePlugin;
+
+impl Plugin for LoadGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::LoadGame), setup);
+    }
+}
+
+fn setup(mut commands: Commands) {
+    let slots = list_saves();
+
+    commands.spawn((
+        StateScoped(GameState::LoadGame),
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            if slots.is_empty() {
+                parent.spawn((
+                    Text::new("No saved games"),
+                    TextFont {
+                        font_size: 33.0,
+                        ..default()
+                    },
+                    Node {
+                        margin: UiRect::bottom(Val::Px(20.)),
+                        ..default()
+                    },
+                ));
+            }
+
+            for slot in slots.into_iter().take(MAX_LISTED_SAVES) {
+                parent
+                    .spawn((Name::new(format!("{}Button", slot.name)), menu_button(slot.name)))
+                    .observe(
+                        move |_: Trigger<Pointer<Click>>,
+                              mut commands: Commands,
+                              mut next_state_res: ResMut<NextState<GameState>>| {
+                            commands.insert_resource(LoadedSave(slot.path.clone()));
+                            next_state_res.set(GameState::Playing);
+                        },
+                    );
+            }
+
+            parent.spawn(get_state_transition_button("Back", GameState::StartMenu));
+        })),
+    ));
+}
@@ -1,18 +1,137 @@
-use bevy::prelude::*;
+use bevy::{ecs::spawn::SpawnWith, ecs::system::IntoObserverSystem, prelude::*};
 
-use crate::menus::{game_selection::GameSelectionPlugin, start_menu::StartMenuPlugin};
+use crate::menus::{
+    game_selection::GameSelectionPlugin, load_game::LoadGamePlugin, options::OptionsPlugin,
+    post_game::PostGamePlugin, replays::ReplaysPlugin, start_menu::StartMenuPlugin,
+};
 
 pub mod game_selection;
+pub mod load_game;
+pub mod options;
+pub mod post_game;
+pub mod replays;
 pub mod start_menu;
 
 pub struct MenusPlugin;
 
 impl Plugin for MenusPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((StartMenuPlugin, GameSelectionPlugin));
+        app.register_type::<InteractionPalette>()
+            .add_plugins((
+                StartMenuPlugin,
+                GameSelectionPlugin,
+                LoadGamePlugin,
+                OptionsPlugin,
+                PostGamePlugin,
+                ReplaysPlugin,
+            ))
+            .add_systems(Update, apply_interaction_palette);
     }
 }
 
 pub const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 pub const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 pub const PRESSED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub(crate) struct InteractionPalette {
+    pub none: Color,
+    pub hovered: Color,
+    pub pressed: Color,
+}
+
+fn apply_interaction_palette(
+    mut palette_query: Query<
+        (&Interaction, &InteractionPalette, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, palette, mut background) in &mut palette_query {
+        *background = match interaction {
+            Interaction::None => palette.none,
+            Interaction::Hovered => palette.hovered,
+            Interaction::Pressed => palette.pressed,
+        }
+        .into();
+    }
+}
+
+/// A menu button, reacting to clicks is left to an observer added by the caller.
+pub(crate) fn menu_button(text: impl Into<String>) -> impl Bundle {
+    (
+        Button,
+        Node {
+            min_width: Val::Px(150.),
+            height: Val::Px(65.),
+            padding: UiRect::horizontal(Val::Px(10.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            margin: UiRect {
+                bottom: Val::Px(5.),
+                ..default()
+            },
+            ..default()
+        },
+        BackgroundColor(NORMAL_BUTTON),
+        InteractionPalette {
+            none: NORMAL_BUTTON,
+            hovered: HOVERED_BUTTON,
+            pressed: PRESSED_BUTTON,
+        },
+        children![(
+            Text::new(text),
+            TextFont {
+                font_size: 33.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9))
+        )],
+    )
+}
+
+/// A small square button, typically used in pairs to step through the values of a setting.
+pub(crate) fn arrow_button<M: 'static>(
+    text: &'static str,
+    on_click: impl IntoObserverSystem<Pointer<Click>, (), M> + Sync,
+) -> impl Bundle {
+    (
+        Node::default(),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(45.),
+                        height: Val::Px(45.),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(NORMAL_BUTTON),
+                    InteractionPalette {
+                        none: NORMAL_BUTTON,
+                        hovered: HOVERED_BUTTON,
+                        pressed: PRESSED_BUTTON,
+                    },
+                    children![(
+                        Text::new(text),
+                        TextFont {
+                            font_size: 28.0,
+                            ..default()
+                        }
+                    )],
+                ))
+                .observe(on_click);
+        })),
+    )
+}
+
+/// Steps through `values` starting from `current`, wrapping around at both ends.
+pub(crate) fn cycle<T: PartialEq + Copy>(values: &[T], current: T, step: i32) -> T {
+    let index = values
+        .iter()
+        .position(|value| *value == current)
+        .unwrap_or(0) as i32;
+    values[(index + step).rem_euclid(values.len() as i32) as usize]
+}
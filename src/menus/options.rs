@@ -0,0 +1,413 @@
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    config::settings::{BarVisibility, DisplayMode, Settings, ShadowQuality},
+    game_states::GameState,
+    input::{Action, BindingError, Chord, InputButton},
+    menus::{
+        HOVERED_BUTTON, InteractionPalette, NORMAL_BUTTON, PRESSED_BUTTON, arrow_button, cycle,
+        start_menu::get_state_transition_button,
+    },
+};
+
+const RESOLUTIONS: [(u32, u32); 5] = [
+    (1280, 720),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1440),
+    (3840, 2160),
+];
+
+pub struct OptionsPlugin;
+
+impl Plugin for OptionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Options), setup)
+            .add_systems(OnExit(GameState::Options), save_settings)
+            .add_systems(
+                Update,
+                (
+                    update_option_values.run_if(resource_changed::<Settings>),
+                    capture_binding.run_if(resource_exists::<Rebinding>),
+                    update_binding_values,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Options)),
+            );
+    }
+}
+
+/// A line of the options screen, each one editing a single setting.
+#[derive(Reflect, Debug, Clone, Copy, EnumIter)]
+enum OptionRow {
+    Resolution,
+    DisplayMode,
+    VSync,
+    Shadows,
+    CameraSpeed,
+    OrthographicZoomSpeed,
+    PerspectiveZoomSpeed,
+    Volume,
+    UnitBars,
+}
+
+/// Text showing the chord currently bound to an [`Action`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BindingValue(Action);
+
+/// Present while waiting for the player to press the new chord of an action.
+#[derive(Resource, Debug)]
+struct Rebinding {
+    action: Action,
+    /// Why the last chord pressed was refused
+    error: Option<BindingError>,
+}
+
+/// Text showing the current value of an [`OptionRow`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct OptionValue(OptionRow);
+
+impl OptionRow {
+    fn label(&self) -> &'static str {
+        match self {
+            OptionRow::Resolution => "Resolution",
+            OptionRow::DisplayMode => "Window mode",
+            OptionRow::VSync => "VSync",
+            OptionRow::Shadows => "Shadows",
+            OptionRow::CameraSpeed => "Camera speed",
+            OptionRow::OrthographicZoomSpeed => "Zoom speed",
+            OptionRow::PerspectiveZoomSpeed => "Perspective zoom speed",
+            OptionRow::Volume => "Volume",
+            OptionRow::UnitBars => "Unit bars",
+        }
+    }
+
+    fn value(&self, settings: &Settings) -> String {
+        match self {
+            OptionRow::Resolution => {
+                format!("{}x{}", settings.display.width, settings.display.height)
+            }
+            OptionRow::DisplayMode => format!("{:?}", settings.display.mode),
+            OptionRow::VSync => if settings.display.vsync { "On" } else { "Off" }.to_string(),
+            OptionRow::Shadows => format!("{:?}", settings.graphics.shadow_quality),
+            OptionRow::CameraSpeed => format!("{:.0}", settings.camera.movement_speed),
+            OptionRow::OrthographicZoomSpeed => {
+                format!("{:.1}", settings.camera.orthographic_zoom_speed * 1000.)
+            }
+            OptionRow::PerspectiveZoomSpeed => {
+                format!("{:.2}", settings.camera.perspective_zoom_speed)
+            }
+            OptionRow::Volume => format!("{:.0}%", settings.audio.master_volume * 100.),
+            OptionRow::UnitBars => format!("{:?}", settings.interface.unit_bars),
+        }
+    }
+
+    fn adjust(&self, settings: &mut Settings, step: i32) {
+        match self {
+            OptionRow::Resolution => {
+                let display = &mut settings.display;
+                (display.width, display.height) =
+                    cycle(&RESOLUTIONS, (display.width, display.height), step);
+            }
+            OptionRow::DisplayMode => {
+                let modes: Vec<DisplayMode> = DisplayMode::iter().collect();
+                settings.display.mode = cycle(&modes, settings.display.mode, step);
+            }
+            OptionRow::VSync => settings.display.vsync = !settings.display.vsync,
+            OptionRow::Shadows => {
+                let qualities: Vec<ShadowQuality> = ShadowQuality::iter().collect();
+                settings.graphics.shadow_quality =
+                    cycle(&qualities, settings.graphics.shadow_quality, step);
+            }
+            OptionRow::CameraSpeed => {
+                settings.camera.movement_speed =
+                    (settings.camera.movement_speed + step as f32).clamp(1., 50.);
+            }
+            OptionRow::OrthographicZoomSpeed => {
+                settings.camera.orthographic_zoom_speed = (settings.camera.orthographic_zoom_speed
+                    + step as f32 * 0.0001)
+                    .clamp(0.0001, 0.01);
+            }
+            OptionRow::PerspectiveZoomSpeed => {
+                settings.camera.perspective_zoom_speed =
+                    (settings.camera.perspective_zoom_speed + step as f32 * 0.01).clamp(0.01, 0.5);
+            }
+            OptionRow::Volume => {
+                settings.audio.master_volume =
+                    (settings.audio.master_volume + step as f32 * 0.1).clamp(0., 1.);
+            }
+            OptionRow::UnitBars => {
+                let modes: Vec<BarVisibility> = BarVisibility::iter().collect();
+                settings.interface.unit_bars = cycle(&modes, settings.interface.unit_bars, step);
+            }
+        }
+    }
+}
+
+fn setup(mut commands: Commands, settings: Res<Settings>) {
+    let rows: Vec<(OptionRow, String)> = OptionRow::iter()
+        .map(|row| (row, row.value(&settings)))
+        .collect();
+    let bindings: Vec<(Action, String)> = Action::iter()
+        .map(|action| (action, settings.input.chord(action).to_string()))
+        .collect();
+
+    commands.spawn((
+        StateScoped(GameState::Options),
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        children![
+            (
+                Node {
+                    column_gap: Val::Px(60.),
+                    margin: UiRect::bottom(Val::Px(20.)),
+                    ..default()
+                },
+                children![
+                    (
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+                            for (row, value) in rows {
+                                parent.spawn(option_row(row, value));
+                            }
+                        })),
+                    ),
+                    (
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+                            for (action, chord) in bindings {
+                                parent.spawn(binding_row(action, chord));
+                            }
+                        })),
+                    ),
+                ],
+            ),
+            get_state_transition_button("Back", GameState::StartMenu),
+        ],
+    ));
+}
+
+fn option_row(row: OptionRow, value: String) -> impl Bundle {
+    (
+        Name::new(format!("{:?}Option", row)),
+        Node {
+            align_items: AlignItems::Center,
+            margin: UiRect::bottom(Val::Px(5.)),
+            ..default()
+        },
+        children![
+            (
+                Text::new(row.label()),
+                option_font(),
+                Node {
+                    width: Val::Px(350.),
+                    ..default()
+                },
+            ),
+            arrow_button(
+                "<",
+                move |_: Trigger<Pointer<Click>>, mut settings: ResMut<Settings>| {
+                    row.adjust(&mut settings, -1);
+                }
+            ),
+            (
+                OptionValue(row),
+                Text::new(value),
+                option_font(),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    width: Val::Px(200.),
+                    ..default()
+                },
+            ),
+            arrow_button(
+                ">",
+                move |_: Trigger<Pointer<Click>>, mut settings: ResMut<Settings>| {
+                    row.adjust(&mut settings, 1);
+                }
+            ),
+        ],
+    )
+}
+
+fn option_font() -> TextFont {
+    TextFont {
+        font_size: 28.0,
+        ..default()
+    }
+}
+
+fn update_option_values(
+    settings: Res<Settings>,
+    mut values_query: Query<(&mut Text, &OptionValue)>,
+) {
+    for (mut text, OptionValue(row)) in values_query.iter_mut() {
+        text.0 = row.value(&settings);
+    }
+}
+
+fn binding_row(action: Action, chord: String) -> impl Bundle {
+    (
+        Name::new(format!("{:?}Binding", action)),
+        Node {
+            align_items: AlignItems::Center,
+            margin: UiRect::bottom(Val::Px(3.)),
+            ..default()
+        },
+        children![
+            (
+                Text::new(action.label()),
+                binding_font(),
+                Node {
+                    width: Val::Px(200.),
+                    ..default()
+                },
+            ),
+            (
+                Node::default(),
+                Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+                    parent
+                        .spawn((
+                            Button,
+                            Node {
+                                width: Val::Px(250.),
+                                height: Val::Px(32.),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            BackgroundColor(NORMAL_BUTTON),
+                            InteractionPalette {
+                                none: NORMAL_BUTTON,
+                                hovered: HOVERED_BUTTON,
+                                pressed: PRESSED_BUTTON,
+                            },
+                            children![(BindingValue(action), Text::new(chord), binding_font())],
+                        ))
+                        .observe(move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                            commands.insert_resource(Rebinding {
+                                action,
+                                error: None,
+                            });
+                        });
+                })),
+            ),
+        ],
+    )
+}
+
+fn binding_font() -> TextFont {
+    TextFont {
+        font_size: 20.0,
+        ..default()
+    }
+}
+
+fn is_modifier(key: KeyCode) -> bool {
+    matches!(
+        key,
+        KeyCode::ShiftLeft
+            | KeyCode::ShiftRight
+            | KeyCode::ControlLeft
+            | KeyCode::ControlRight
+            | KeyCode::AltLeft
+            | KeyCode::AltRight
+            | KeyCode::SuperLeft
+            | KeyCode::SuperRight
+    )
+}
+
+// The new chord is made of the modifiers being held plus the first other key or mouse button
+// pressed after the binding was clicked. Escape on its own cancels, and so does clicking one of
+// the buttons of the menu, which then handles the click as usual.
+fn capture_binding(
+    mut commands: Commands,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings: ResMut<Settings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    buttons_query: Query<&Interaction, With<Button>>,
+) {
+    let modifiers: Vec<InputButton> = keys
+        .get_pressed()
+        .filter(|key| is_modifier(**key))
+        .map(|key| InputButton::Key(*key))
+        .collect();
+
+    let cancel_key = keys.just_pressed(KeyCode::Escape) && modifiers.is_empty();
+    let on_button = mouse.get_just_pressed().next().is_some()
+        && buttons_query
+            .iter()
+            .any(|interaction| *interaction != Interaction::None);
+    if cancel_key || on_button {
+        commands.remove_resource::<Rebinding>();
+        return;
+    }
+
+    let trigger = keys
+        .get_just_pressed()
+        .find(|key| !is_modifier(**key))
+        .map(|key| InputButton::Key(*key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|button| InputButton::Mouse(*button))
+        });
+    let Some(trigger) = trigger else {
+        return;
+    };
+
+    let mut chord = modifiers;
+    chord.push(trigger);
+    let chord = Chord(chord);
+
+    // Refused chords leave the binding waiting for another one
+    if let Err(error) = settings.input.check(rebinding.action, &chord) {
+        rebinding.error = Some(error);
+        return;
+    }
+
+    settings.input.rebind(rebinding.action, chord);
+    commands.remove_resource::<Rebinding>();
+}
+
+fn update_binding_values(
+    settings: Res<Settings>,
+    rebinding: Option<Res<Rebinding>>,
+    mut values_query: Query<(&mut Text, &BindingValue)>,
+) {
+    for (mut text, BindingValue(action)) in values_query.iter_mut() {
+        let value = match rebinding {
+            Some(ref rebinding) if rebinding.action == *action => match rebinding.error {
+                Some(error) => format!("{error}, press another..."),
+                None => "Press a key...".to_string(),
+            },
+            _ => settings.input.chord(*action).to_string(),
+        };
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+}
+
+fn save_settings(mut commands: Commands, settings: Res<Settings>) -> Result {
+    commands.remove_resource::<Rebinding>();
+    settings.save()
+}
@@ -0,0 +1,116 @@
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+
+use crate::{
+    game_states::GameState,
+    menus::start_menu::get_state_transition_button,
+    victory::{MatchResult, PlayerSummary},
+};
+
+pub struct PostGamePlugin;
+
+impl Plugin for PostGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::PostGame), setup);
+    }
+}
+
+const COLUMNS: [&str; 7] = [
+    "Player", "Team", "Produced", "Lost", "Killed", "Gathered", "Score",
+];
+
+fn setup(mut commands: Commands, match_result: Res<MatchResult>) {
+    let title = match (match_result.winning_team, match_result.local_team) {
+        (None, _) => "Draw",
+        (Some(winner), Some(local)) if winner == local => "Victory",
+        (Some(_), Some(_)) => "Defeat",
+        (Some(_), None) => "Match over",
+    };
+    let rows: Vec<(Color, Vec<String>)> = match_result
+        .players
+        .iter()
+        .map(|summary| (summary.color.color(), summary_row(summary)))
+        .collect();
+
+    commands.spawn((
+        StateScoped(GameState::PostGame),
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 50.0,
+                    ..default()
+                },
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.)),
+                    ..default()
+                },
+            ));
+
+            parent.spawn(table_row(
+                Color::srgb(0.9, 0.9, 0.9),
+                COLUMNS.iter().map(ToString::to_string).collect(),
+            ));
+            for (color, cells) in rows {
+                parent.spawn(table_row(color, cells));
+            }
+
+            parent
+                .spawn(Node {
+                    margin: UiRect::top(Val::Px(20.)),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(get_state_transition_button(
+                        "Main Menu",
+                        GameState::StartMenu,
+                    ));
+                });
+        })),
+    ));
+}
+
+fn summary_row(summary: &PlayerSummary) -> Vec<String> {
+    vec![
+        summary.name.clone(),
+        summary.team.to_string(),
+        summary.stats.units_produced.to_string(),
+        summary.stats.units_lost.to_string(),
+        summary.stats.units_killed.to_string(),
+        summary.stats.resources_gathered.to_string(),
+        summary.score.to_string(),
+    ]
+}
+
+fn table_row(color: Color, cells: Vec<String>) -> impl Bundle {
+    (
+        Node {
+            margin: UiRect::bottom(Val::Px(5.)),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for cell in cells {
+                parent.spawn((
+                    Text::new(cell),
+                    TextFont {
+                        font_size: 26.0,
+                        ..default()
+                    },
+                    TextColor(color),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    Node {
+                        width: Val::Px(130.),
+                        ..default()
+                    },
+                ));
+            }
+        })),
+    )
+}
@@ -0,0 +1,75 @@
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+
+use crate::{
+    game_states::GameState,
+    match_settings::MatchSettings,
+    menus::{menu_button, start_menu::get_state_transition_button},
+    replay::{Replay, ReplayPlayback, list_replays},
+};
+
+/// Only the most recent replays are listed, older ones are still on disk.
+const MAX_LISTED_REPLAYS: usize = 8;
+
+pub struct ReplaysPlugin;
+
+impl Plugin for ReplaysPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Replays), setup);
+    }
+}
+
+fn setup(mut commands: Commands) {
+    let slots = list_replays();
+
+    commands.spawn((
+        StateScoped(GameState::Replays),
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            if slots.is_empty() {
+                parent.spawn((
+                    Text::new("No recorded matches"),
+                    TextFont {
+                        font_size: 33.0,
+                        ..default()
+                    },
+                    Node {
+                        margin: UiRect::bottom(Val::Px(20.)),
+                        ..default()
+                    },
+                ));
+            }
+
+            for slot in slots.into_iter().take(MAX_LISTED_REPLAYS) {
+                parent
+                    .spawn((Name::new(format!("{}Button", slot.name)), menu_button(slot.name)))
+                    .observe(
+                        move |_: Trigger<Pointer<Click>>,
+                              mut commands: Commands,
+                              mut next_state_res: ResMut<NextState<GameState>>| {
+                            let replay = match Replay::load(&slot.path) {
+                                Ok(replay) => replay,
+                                Err(error) => {
+                                    warn!("Could not load {}: {error}", slot.path.display());
+                                    return;
+                                }
+                            };
+
+                            // The match is set up exactly as it was when recorded
+                            commands.insert_resource::<MatchSettings>(replay.settings.clone());
+                            commands.insert_resource(ReplayPlayback(replay));
+                            next_state_res.set(GameState::Playing);
+                        },
+                    );
+            }
+
+            parent.spawn(get_state_transition_button("Back", GameState::StartMenu));
+        })),
+    ));
+}
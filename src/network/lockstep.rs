@@ -0,0 +1,287 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{network::transport::Transport, orders::PlayerCommand, players::PlayerId};
+
+/// Ticks played with the commands of a single turn.
+pub const TURN_TICKS: u64 = 4;
+/// Commands given during a turn are played this many turns later, leaving them time to reach
+/// the other players.
+pub const INPUT_DELAY_TURNS: u64 = 2;
+/// Sent turns are repeated until this many newer ones were sent, as UDP may drop them.
+const RESENT_TURNS: u64 = 8;
+/// Checksums older than this many ticks are forgotten.
+const CHECKSUM_HISTORY: u64 = 64 * TURN_TICKS;
+
+/// Hash of the simulated state at the start of a tick, compared between players to find desyncs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickChecksum {
+    pub tick: u64,
+    pub value: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TurnPacket {
+    player: PlayerId,
+    turn: u64,
+    commands: Vec<PlayerCommand>,
+    checksum: Option<TickChecksum>,
+}
+
+/// Exchanges the commands of every player turn by turn. A turn can only be played once the
+/// commands of all players for it have arrived.
+pub struct LockstepSession {
+    transport: Box<dyn Transport>,
+    local: PlayerId,
+    players: Vec<PlayerId>,
+    /// Commands of each player by turn, turns are dropped once played
+    turns: BTreeMap<u64, BTreeMap<PlayerId, Vec<PlayerCommand>>>,
+    /// Packets of the last turns sent from here, resent on every poll
+    sent: BTreeMap<u64, Vec<u8>>,
+    next_turn: u64,
+    /// Turns before this one were played
+    next_to_play: u64,
+    checksums: BTreeMap<u64, BTreeMap<PlayerId, u64>>,
+    desync: Option<u64>,
+    desync_reported: bool,
+}
+
+impl LockstepSession {
+    pub fn new(transport: Box<dyn Transport>, local: PlayerId, players: Vec<PlayerId>) -> Self {
+        // Nobody could give commands before the match started
+        let turns = (0..INPUT_DELAY_TURNS)
+            .map(|turn| {
+                let commands = players.iter().map(|player| (*player, Vec::new())).collect();
+                (turn, commands)
+            })
+            .collect();
+
+        Self {
+            transport,
+            local,
+            players,
+            turns,
+            sent: BTreeMap::new(),
+            next_turn: INPUT_DELAY_TURNS,
+            next_to_play: 0,
+            checksums: BTreeMap::new(),
+            desync: None,
+            desync_reported: false,
+        }
+    }
+
+    /// The turn the next call to [`Self::submit`] sends commands for.
+    pub fn next_turn(&self) -> u64 {
+        self.next_turn
+    }
+
+    /// Sends the local commands for [`Self::next_turn`] along with the checksum of the last
+    /// tick played.
+    pub fn submit(&mut self, commands: Vec<PlayerCommand>, checksum: Option<TickChecksum>) {
+        let turn = self.next_turn;
+        self.next_turn += 1;
+
+        let packet = TurnPacket {
+            player: self.local,
+            turn,
+            commands,
+            checksum,
+        };
+        let bytes = match serde_json::to_vec(&packet) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                error!("Could not encode turn {turn}: {error}");
+                return;
+            }
+        };
+
+        self.receive_packet(packet);
+        self.transport.send(&bytes);
+        self.sent.insert(turn, bytes);
+        self.sent = self.sent.split_off(&turn.saturating_sub(RESENT_TURNS));
+    }
+
+    /// Resends the last turns and stores the packets received from the other players.
+    pub fn poll(&mut self) {
+        for bytes in self.sent.values() {
+            self.transport.send(bytes);
+        }
+
+        for bytes in self.transport.receive() {
+            match serde_json::from_slice::<TurnPacket>(&bytes) {
+                Ok(packet) => self.receive_packet(packet),
+                Err(error) => warn!("Ignoring malformed turn packet: {error}"),
+            }
+        }
+    }
+
+    fn receive_packet(&mut self, packet: TurnPacket) {
+        if !self.players.contains(&packet.player) {
+            return;
+        }
+
+        if let Some(checksum) = packet.checksum {
+            self.record_checksum(packet.player, checksum);
+        }
+
+        // Turns already played were removed, duplicates of them are ignored
+        if packet.turn >= self.next_to_play {
+            self.turns
+                .entry(packet.turn)
+                .or_default()
+                .entry(packet.player)
+                .or_insert(packet.commands);
+        }
+    }
+
+    fn record_checksum(&mut self, player: PlayerId, checksum: TickChecksum) {
+        let values = self.checksums.entry(checksum.tick).or_default();
+        values.insert(player, checksum.value);
+
+        if values.values().any(|value| *value != checksum.value) && self.desync.is_none() {
+            self.desync = Some(checksum.tick);
+        }
+
+        self.checksums = self
+            .checksums
+            .split_off(&checksum.tick.saturating_sub(CHECKSUM_HISTORY));
+    }
+
+    /// Whether the commands of every player for `turn` have arrived.
+    pub fn is_ready(&self, turn: u64) -> bool {
+        self.turns.get(&turn).is_some_and(|commands| {
+            self.players
+                .iter()
+                .all(|player| commands.contains_key(player))
+        })
+    }
+
+    /// Removes the commands of `turn`, ordered by player so that every machine plays them in the
+    /// same order.
+    pub fn take(&mut self, turn: u64) -> Vec<(PlayerId, PlayerCommand)> {
+        self.next_to_play = self.next_to_play.max(turn + 1);
+        self.turns
+            .remove(&turn)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|(player, commands)| {
+                commands.into_iter().map(move |command| (player, command))
+            })
+            .collect()
+    }
+
+    /// The first tick at which players disagreed on the state of the match, only returned once.
+    pub fn take_desync(&mut self) -> Option<u64> {
+        if self.desync_reported {
+            return None;
+        }
+        self.desync_reported = self.desync.is_some();
+        self.desync
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+
+    use super::*;
+    use crate::{network::transport::LoopbackTransport, orders::UnitId};
+
+    fn sessions(players: usize) -> Vec<LockstepSession> {
+        let ids: Vec<PlayerId> = (0..players as u8).map(PlayerId).collect();
+
+        LoopbackTransport::connect(players)
+            .into_iter()
+            .zip(ids.iter())
+            .map(|(transport, id)| LockstepSession::new(Box::new(transport), *id, ids.clone()))
+            .collect()
+    }
+
+    fn move_command(unit: u32) -> PlayerCommand {
+        PlayerCommand::Move {
+            units: vec![UnitId(unit)],
+            target: Vec2::new(1., 2.),
+        }
+    }
+
+    #[test]
+    fn first_turns_are_ready_without_any_packet() {
+        let session = sessions(2).remove(0);
+
+        for turn in 0..INPUT_DELAY_TURNS {
+            assert!(session.is_ready(turn));
+        }
+        assert!(!session.is_ready(INPUT_DELAY_TURNS));
+    }
+
+    #[test]
+    fn turn_waits_for_every_player() {
+        let mut sessions = sessions(2);
+
+        sessions[0].submit(vec![move_command(1)], None);
+        sessions[1].poll();
+        assert!(!sessions[0].is_ready(INPUT_DELAY_TURNS));
+        assert!(!sessions[1].is_ready(INPUT_DELAY_TURNS));
+
+        sessions[1].submit(vec![move_command(2)], None);
+        sessions[0].poll();
+        assert!(sessions[0].is_ready(INPUT_DELAY_TURNS));
+        assert!(sessions[1].is_ready(INPUT_DELAY_TURNS));
+    }
+
+    #[test]
+    fn every_player_plays_the_same_commands() {
+        let mut sessions = sessions(3);
+
+        for (index, session) in sessions.iter_mut().enumerate() {
+            session.submit(vec![move_command(index as u32)], None);
+        }
+        for session in sessions.iter_mut() {
+            session.poll();
+        }
+
+        let played: Vec<_> = sessions
+            .iter_mut()
+            .map(|session| session.take(INPUT_DELAY_TURNS))
+            .collect();
+        assert_eq!(played[0].len(), 3);
+        assert!(played.iter().all(|commands| *commands == played[0]));
+    }
+
+    #[test]
+    fn resent_turns_are_not_played_twice() {
+        let mut sessions = sessions(2);
+
+        sessions[0].submit(vec![move_command(1)], None);
+        sessions[1].submit(Vec::new(), None);
+        sessions[0].poll();
+        sessions[1].poll();
+        for turn in 0..INPUT_DELAY_TURNS {
+            sessions[1].take(turn);
+        }
+        assert_eq!(sessions[1].take(INPUT_DELAY_TURNS).len(), 1);
+
+        // The turn keeps being resent until newer ones push it out
+        sessions[0].poll();
+        sessions[1].poll();
+        assert!(!sessions[1].is_ready(INPUT_DELAY_TURNS));
+    }
+
+    #[test]
+    fn diverging_checksums_are_reported_once() {
+        let mut sessions = sessions(2);
+
+        sessions[0].submit(Vec::new(), Some(TickChecksum { tick: 0, value: 1 }));
+        sessions[1].submit(Vec::new(), Some(TickChecksum { tick: 0, value: 1 }));
+        sessions[0].poll();
+        assert_eq!(sessions[0].take_desync(), None);
+
+        sessions[0].submit(Vec::new(), Some(TickChecksum { tick: 4, value: 1 }));
+        sessions[1].submit(Vec::new(), Some(TickChecksum { tick: 4, value: 2 }));
+        sessions[0].poll();
+        assert_eq!(sessions[0].take_desync(), Some(4));
+        assert_eq!(sessions[0].take_desync(), None);
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+use crate::{
+    config::network::{NetworkConfig, NetworkMode},
+    game_states::GameState,
+    match_settings::{MatchSettings, SlotKind},
+    network::{
+        lockstep::{INPUT_DELAY_TURNS, LockstepSession, TURN_TICKS, TickChecksum},
+        transport::{LoopbackTransport, Transport, UdpTransport},
+    },
+    orders::{LocalCommands, ScheduledCommands, UnitId},
+    players::{LocalPlayer, Player, PlayerId, spawn_players},
+    replay::ReplayPlayback,
+    save::LoadedSave,
+    simulation::{SimulationTick, TickGate, restore_translations, state_checksum},
+    units::health::Health,
+};
+
+pub mod lockstep;
+pub mod transport;
+
+pub struct NetworkPlugin;
+
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            start_lockstep.after(spawn_players).run_if(
+                not(resource_exists::<LoadedSave>).and(not(resource_exists::<ReplayPlayback>)),
+            ),
+        )
+        .add_systems(OnExit(GameState::Playing), stop_lockstep)
+        .add_systems(
+            FixedFirst,
+            lockstep_tick
+                .after(restore_translations)
+                .run_if(in_state(GameState::Playing).and(resource_exists::<Lockstep>)),
+        );
+    }
+}
+
+/// Session of a match played in lockstep, only present while one is running.
+#[derive(Resource)]
+pub struct Lockstep(pub LockstepSession);
+
+fn start_lockstep(
+    mut commands: Commands,
+    network_config: Res<NetworkConfig>,
+    match_settings: Res<MatchSettings>,
+    mut local_player: ResMut<LocalPlayer>,
+) {
+    let (transport, players): (Box<dyn Transport>, Vec<PlayerId>) = match &network_config.mode {
+        NetworkMode::Offline => return,
+        NetworkMode::Loopback => {
+            let Some(transport) = LoopbackTransport::connect(1).pop() else {
+                return;
+            };
+            (Box::new(transport), vec![local_player.0])
+        }
+        NetworkMode::Udp {
+            bind,
+            peers,
+            local_player: player,
+            ..
+        } => {
+            let transport = match UdpTransport::bind(*bind, peers.clone()) {
+                Ok(transport) => transport,
+                Err(error) => {
+                    error!("Could not bind {bind}, playing offline: {error}");
+                    return;
+                }
+            };
+            local_player.0 = *player;
+
+            // Computer players are simulated on every machine, only humans send commands
+            let humans = match_settings
+                .open_slots()
+                .filter(|(_, slot)| slot.kind == SlotKind::Human)
+                .map(|(index, _)| PlayerId(index as u8))
+                .collect();
+            (Box::new(transport), humans)
+        }
+    };
+
+    info!("Playing in lockstep with players {players:?}");
+    commands.insert_resource(Lockstep(LockstepSession::new(
+        transport,
+        local_player.0,
+        players,
+    )));
+}
+
+fn stop_lockstep(mut commands: Commands) {
+    commands.remove_resource::<Lockstep>();
+}
+
+/// Exchanges commands at the start of every turn and holds the simulation until the commands of
+/// all players for the turn have arrived.
+fn lockstep_tick(
+    mut lockstep: ResMut<Lockstep>,
+    mut gate: ResMut<TickGate>,
+    mut local_commands: ResMut<LocalCommands>,
+    mut scheduled: ResMut<ScheduledCommands>,
+    tick: Res<SimulationTick>,
+    units_query: Query<(&UnitId, &Transform, &Health)>,
+    players_query: Query<&Player>,
+) {
+    let session = &mut lockstep.0;
+    session.poll();
+
+    if let Some(tick) = session.take_desync() {
+        error!("Players went out of sync at tick {tick}");
+    }
+
+    if !tick.0.is_multiple_of(TURN_TICKS) {
+        gate.open = true;
+        return;
+    }
+
+    let turn = tick.0 / TURN_TICKS;
+    // The gate stays closed on this tick until the turn is ready, commands are only sent once
+    if session.next_turn() <= turn + INPUT_DELAY_TURNS {
+        let checksum = TickChecksum {
+            tick: tick.0,
+            value: state_checksum(units_query.iter(), players_query.iter()),
+        };
+        session.submit(local_commands.0.drain(..).collect(), Some(checksum));
+    }
+
+    gate.open = session.is_ready(turn);
+    if gate.open {
+        scheduled
+            .0
+            .entry(tick.0)
+            .or_default()
+            .extend(session.take(turn));
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind},
+    net::{SocketAddr, UdpSocket},
+    sync::{Arc, Mutex},
+};
+
+use bevy::prelude::*;
+
+/// Largest payload a UDP datagram can carry.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Sends packets to every other player, delivery and ordering are not guaranteed.
+pub trait Transport: Send + Sync {
+    fn send(&mut self, packet: &[u8]);
+    /// Packets received since the last call
+    fn receive(&mut self) -> Vec<Vec<u8>>;
+}
+
+pub struct UdpTransport {
+    socket: UdpSocket,
+    peers: Vec<SocketAddr>,
+}
+
+impl UdpTransport {
+    pub fn bind(address: SocketAddr, peers: Vec<SocketAddr>) -> io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self { socket, peers })
+    }
+}
+
+impl Transport for UdpTransport {
+    fn send(&mut self, packet: &[u8]) {
+        for peer in self.peers.iter() {
+            if let Err(error) = self.socket.send_to(packet, peer) {
+                warn!("Could not send packet to {peer}: {error}");
+            }
+        }
+    }
+
+    fn receive(&mut self) -> Vec<Vec<u8>> {
+        let mut packets = Vec::new();
+        let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((size, sender)) if self.peers.contains(&sender) => {
+                    packets.push(buffer[..size].to_vec());
+                }
+                Ok((_, sender)) => warn!("Ignoring packet from unknown sender {sender}"),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                // Windows reports packets refused by a peer that is not listening yet
+                Err(error) if error.kind() == ErrorKind::ConnectionReset => continue,
+                Err(error) => {
+                    warn!("Could not receive packets: {error}");
+                    break;
+                }
+            }
+        }
+
+        packets
+    }
+}
+
+type Inbox = Arc<Mutex<VecDeque<Vec<u8>>>>;
+
+/// Connects players living in the same process through memory, used by tests and
+/// [`crate::config::network::NetworkMode::Loopback`].
+pub struct LoopbackTransport {
+    inbox: Inbox,
+    peers: Vec<Inbox>,
+}
+
+impl LoopbackTransport {
+    /// One transport per player, each sending to all the others.
+    pub fn connect(players: usize) -> Vec<LoopbackTransport> {
+        let inboxes: Vec<Inbox> = (0..players).map(|_| Inbox::default()).collect();
+
+        (0..players)
+            .map(|player| LoopbackTransport {
+                inbox: inboxes[player].clone(),
+                peers: inboxes
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != player)
+                    .map(|(_, inbox)| inbox.clone())
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl Transport for LoopbackTransport {
+    fn send(&mut self, packet: &[u8]) {
+        for peer in self.peers.iter() {
+            peer.lock().unwrap().push_back(packet.to_vec());
+        }
+    }
+
+    fn receive(&mut self) -> Vec<Vec<u8>> {
+        self.inbox.lock().unwrap().drain(..).collect()
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_states::GameState,
+    orders::{CommandTarget, OrderGiven, OrderKind, UnitId},
+    units::{MoveTo, Selected, Unit, combat::Attack},
+};
+
+/// How long a marker stays on the ground.
+const MARKER_LIFETIME: f32 = 0.6;
+/// Height above the terrain of markers and path previews, so that they are not hidden in it.
+const GROUND_OFFSET: f32 = 0.02;
+
+pub struct OrderFeedbackPlugin;
+
+impl Plugin for OrderFeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup)
+            .add_observer(spawn_marker)
+            .add_systems(
+                Update,
+                (animate_markers, draw_paths).run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Shared by every marker, so that spawning one does not create assets.
+#[derive(Resource)]
+struct MarkerAssets {
+    mesh: Handle<Mesh>,
+    move_material: Handle<StandardMaterial>,
+    attack_material: Handle<StandardMaterial>,
+    ability_material: Handle<StandardMaterial>,
+}
+
+#[derive(Component)]
+struct OrderMarker(Timer);
+
+fn color(kind: OrderKind) -> Color {
+    match kind {
+        OrderKind::Move => Color::srgb_u8(60, 220, 90),
+        OrderKind::Attack => Color::srgb_u8(230, 50, 40),
+        OrderKind::Ability => Color::srgb_u8(90, 140, 255),
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut material = |kind| {
+        materials.add(StandardMaterial {
+            base_color: color(kind),
+            unlit: true,
+            ..default()
+        })
+    };
+
+    commands.insert_resource(MarkerAssets {
+        mesh: meshes.add(Torus::new(0.15, 0.2)),
+        move_material: material(OrderKind::Move),
+        attack_material: material(OrderKind::Attack),
+        ability_material: material(OrderKind::Ability),
+    });
+}
+
+fn spawn_marker(
+    trigger: Trigger<OrderGiven>,
+    mut commands: Commands,
+    assets: Res<MarkerAssets>,
+    units_query: Query<(&UnitId, &Transform)>,
+) {
+    let position = match trigger.target {
+        CommandTarget::Point(point) => point,
+        CommandTarget::Unit(target) => {
+            let Some((_, transform)) = units_query.iter().find(|(id, _)| **id == target) else {
+                return;
+            };
+            transform.translation.xz()
+        }
+        // Self cast abilities are seen on the caster
+        CommandTarget::Caster => return,
+    };
+    let material = match trigger.kind {
+        OrderKind::Move => assets.move_material.clone(),
+        OrderKind::Attack => assets.attack_material.clone(),
+        OrderKind::Ability => assets.ability_material.clone(),
+    };
+
+    commands.spawn((
+        Name::new("OrderMarker"),
+        StateScoped(GameState::Playing),
+        OrderMarker(Timer::from_seconds(MARKER_LIFETIME, TimerMode::Once)),
+        Mesh3d(assets.mesh.clone()),
+        MeshMaterial3d(material),
+        Transform::from_xyz(position.x, GROUND_OFFSET, position.y),
+    ));
+}
+
+/// Markers shrink towards the target until they disappear.
+fn animate_markers(
+    mut commands: Commands,
+    mut markers_query: Query<(Entity, &mut OrderMarker, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (entity, mut marker, mut transform) in markers_query.iter_mut() {
+        if marker.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let scale = 1.5 - marker.0.fraction();
+        transform.scale = Vec3::new(scale, 1., scale);
+    }
+}
+
+/// Lines from the selected units to where they are heading or what they are attacking.
+fn draw_paths(
+    mut gizmos: Gizmos,
+    selected_query: Query<
+        (&Transform, Option<&MoveTo>, Option<&Attack>),
+        (With<Selected>, With<Unit>),
+    >,
+    targets_query: Query<&Transform, With<Unit>>,
+) {
+    for (transform, move_to, attack) in selected_query.iter() {
+        let from = transform.translation.xz();
+
+        let (to, kind) = if let Some(move_to) = move_to {
+            (move_to.target, OrderKind::Move)
+        } else if let Some(target) = attack.and_then(|attack| targets_query.get(attack.target).ok())
+        {
+            (target.translation.xz(), OrderKind::Attack)
+        } else {
+            continue;
+        };
+
+        let path_color = color(kind);
+        gizmos.line(
+            Vec3::new(from.x, GROUND_OFFSET, from.y),
+            Vec3::new(to.x, GROUND_OFFSET, to.y),
+            path_color,
+        );
+        gizmos.circle(
+            Isometry3d::new(
+                Vec3::new(to.x, GROUND_OFFSET, to.y),
+                Quat::from_rotation_arc(Vec3::Z, Vec3::Y),
+            ),
+            0.1,
+            path_color,
+        );
+    }
+}
@@ -0,0 +1,416 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    buildings::Building,
+    config::economy::EconomyConfig,
+    economy::{Gather, ResourceNode, TrainUnit, UnitKind, node_near},
+    game_states::GameState,
+    input::Action,
+    network::Lockstep,
+    players::{LocalPlayer, Owner, PlayerId},
+    replay::ReplayPlayback,
+    save::LoadedSave,
+    simulation::{SimulationSet, SimulationTick},
+    units::{
+        MoveTo, Selected, Unit,
+        combat::Attack,
+        hero::{Abilities, AbilityTarget, AbilityTargeting, CastAbility, CastTarget, Hero},
+        tactics::{AutoOrder, Stance},
+    },
+};
+
+pub struct OrdersPlugin;
+
+impl Plugin for OrdersPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<UnitId>()
+            .register_type::<NextUnitId>()
+            .init_resource::<NextUnitId>()
+            .init_resource::<LocalCommands>()
+            .init_resource::<ScheduledCommands>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (
+                    reset_orders,
+                    reset_unit_ids.run_if(not(resource_exists::<LoadedSave>)),
+                ),
+            )
+            .add_systems(Update, order_hotkeys.run_if(in_state(GameState::Playing)))
+            .add_systems(
+                FixedFirst,
+                schedule_local_commands.run_if(
+                    in_state(GameState::Playing)
+                        .and(not(resource_exists::<Lockstep>))
+                        .and(not(resource_exists::<ReplayPlayback>)),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (assign_unit_ids, apply_commands)
+                    .chain()
+                    .in_set(SimulationSet::Commands),
+            );
+    }
+}
+
+/// Names a unit or a building the same way on every machine taking part in a match, unlike
+/// [`Entity`].
+#[derive(
+    Component,
+    Reflect,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+#[reflect(Component)]
+pub struct UnitId(pub u32);
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct NextUnitId(pub u32);
+
+/// What a command is aimed at, see [`CastTarget`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CommandTarget {
+    Point(Vec2),
+    Unit(UnitId),
+    Caster,
+}
+
+/// Everything a player can tell its units to do. Input is turned into commands instead of
+/// touching units directly, so that they can be played at the same tick on every machine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PlayerCommand {
+    Move {
+        units: Vec<UnitId>,
+        target: Vec2,
+    },
+    Attack {
+        units: Vec<UnitId>,
+        target: UnitId,
+    },
+    Train {
+        unit: UnitKind,
+    },
+    /// Carries resources from the mine at `node` for as long as the units are left alone.
+    Gather {
+        units: Vec<UnitId>,
+        node: Vec2,
+    },
+    CastAbility {
+        caster: UnitId,
+        ability: usize,
+        target: CommandTarget,
+    },
+    CycleStance {
+        units: Vec<UnitId>,
+    },
+    /// Drops whatever the units were doing, they may still pick a fight on their own depending on
+    /// their [`Stance`].
+    Stop {
+        units: Vec<UnitId>,
+    },
+}
+
+/// Commands given on this machine since they were last scheduled.
+#[derive(Resource, Debug, Default)]
+pub struct LocalCommands(pub Vec<PlayerCommand>);
+
+/// Commands of every player waiting for the tick they are played at.
+#[derive(Resource, Debug, Default)]
+pub struct ScheduledCommands(pub BTreeMap<u64, Vec<(PlayerId, PlayerCommand)>>);
+
+/// Triggered when the local player aims an order at a point or a unit, so that it can be shown on
+/// the ground. Nothing observes it in headless runs.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct OrderGiven {
+    pub kind: OrderKind,
+    pub target: CommandTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderKind {
+    Move,
+    Attack,
+    Ability,
+}
+
+/// Present while a move or attack order is waiting for the player to click its target, like
+/// [`AbilityTargeting`] for abilities.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderTargeting {
+    Move,
+    Attack,
+}
+
+/// Turns a click into the order waiting for a target, if any. Returns `false` when nothing was
+/// waiting, so that the click can be handled as usual.
+pub fn complete_order_targeting(
+    commands: &mut Commands,
+    targeting: Option<Res<OrderTargeting>>,
+    local_commands: &mut LocalCommands,
+    units: Vec<UnitId>,
+    target: CommandTarget,
+) -> bool {
+    let Some(targeting) = targeting else {
+        return false;
+    };
+
+    match (*targeting, target) {
+        (OrderTargeting::Move, CommandTarget::Point(point)) => {
+            local_commands.0.push(PlayerCommand::Move {
+                units,
+                target: point,
+            });
+            commands.trigger(OrderGiven {
+                kind: OrderKind::Move,
+                target,
+            });
+        }
+        (OrderTargeting::Attack, CommandTarget::Unit(unit)) => {
+            local_commands.0.push(PlayerCommand::Attack {
+                units,
+                target: unit,
+            });
+            commands.trigger(OrderGiven {
+                kind: OrderKind::Attack,
+                target,
+            });
+        }
+        (order, target) => info!("Cannot give a {order:?} order on {target:?}"),
+    }
+    commands.remove_resource::<OrderTargeting>();
+
+    true
+}
+
+/// Commands left over from the previous match must not be played in this one, whether it starts
+/// from scratch or from a save.
+pub fn reset_orders(mut commands: Commands) {
+    commands.insert_resource(LocalCommands::default());
+    commands.insert_resource(ScheduledCommands::default());
+    commands.remove_resource::<OrderTargeting>();
+}
+
+/// Saves carry on numbering units from where they stopped.
+fn reset_unit_ids(mut commands: Commands) {
+    commands.insert_resource(NextUnitId::default());
+}
+
+fn order_hotkeys(
+    mut commands: Commands,
+    actions: Res<ButtonInput<Action>>,
+    selected_query: Query<&UnitId, With<Selected>>,
+    mut local_commands: ResMut<LocalCommands>,
+) {
+    if actions.just_pressed(Action::CancelAbility) {
+        commands.remove_resource::<OrderTargeting>();
+    }
+    if selected_query.is_empty() {
+        return;
+    }
+
+    for (action, targeting) in [
+        (Action::Move, OrderTargeting::Move),
+        (Action::Attack, OrderTargeting::Attack),
+    ] {
+        if actions.just_pressed(action) {
+            commands.remove_resource::<AbilityTargeting>();
+            commands.insert_resource(targeting);
+        }
+    }
+    if actions.just_pressed(Action::Stop) {
+        local_commands.0.push(PlayerCommand::Stop {
+            units: selected_query.iter().copied().collect(),
+        });
+    }
+}
+
+/// Without other players to wait for, commands are played on the very next tick.
+fn schedule_local_commands(
+    mut local_commands: ResMut<LocalCommands>,
+    mut scheduled: ResMut<ScheduledCommands>,
+    local_player: Res<LocalPlayer>,
+    tick: Res<SimulationTick>,
+) {
+    if local_commands.0.is_empty() {
+        return;
+    }
+
+    scheduled.0.entry(tick.0).or_default().extend(
+        local_commands
+            .0
+            .drain(..)
+            .map(|command| (local_player.0, command)),
+    );
+}
+
+// Query order depends on how entities were allocated, which differs between machines, so new
+// units are sorted on their simulated state before being numbered.
+fn assign_unit_ids(
+    mut commands: Commands,
+    units_query: Query<
+        (Entity, &Owner, &Transform, Has<Hero>),
+        (Or<(With<Unit>, With<Building>)>, Without<UnitId>),
+    >,
+    mut next_id: ResMut<NextUnitId>,
+) {
+    let mut new_units: Vec<_> = units_query.iter().collect();
+    new_units.sort_by(|(_, a_owner, a, a_hero), (_, b_owner, b, b_hero)| {
+        a_owner
+            .0
+            .cmp(&b_owner.0)
+            .then(a_hero.cmp(b_hero))
+            .then(a.translation.x.total_cmp(&b.translation.x))
+            .then(a.translation.z.total_cmp(&b.translation.z))
+    });
+
+    for (entity, ..) in new_units {
+        commands.entity(entity).insert(UnitId(next_id.0));
+        next_id.0 += 1;
+    }
+}
+
+pub fn apply_commands(
+    mut commands: Commands,
+    mut scheduled: ResMut<ScheduledCommands>,
+    mut stances_query: Query<&mut Stance>,
+    units_query: Query<(Entity, &UnitId, &Owner)>,
+    abilities_query: Query<&Abilities>,
+    nodes_query: Query<&Transform, With<ResourceNode>>,
+    mut train_events: EventWriter<TrainUnit>,
+    mut casts: EventWriter<CastAbility>,
+    economy_config: Res<EconomyConfig>,
+    tick: Res<SimulationTick>,
+) {
+    // Commands of a tick that was already played would not be carried out at the same tick on
+    // every machine
+    let pending = scheduled.0.split_off(&tick.0);
+    let stale = std::mem::replace(&mut scheduled.0, pending);
+    if !stale.is_empty() {
+        warn!(
+            "Dropped the commands of {} ticks already played",
+            stale.len()
+        );
+    }
+    let Some(due) = scheduled.0.remove(&tick.0) else {
+        return;
+    };
+
+    let entities: HashMap<UnitId, (Entity, PlayerId)> = units_query
+        .iter()
+        .map(|(entity, id, owner)| (*id, (entity, owner.0)))
+        .collect();
+    // Players can only command their own units, whatever the packets they send
+    let owned_by = |player: PlayerId, units: &[UnitId]| -> Vec<Entity> {
+        units
+            .iter()
+            .filter_map(|id| entities.get(id))
+            .filter(|(_, owner)| *owner == player)
+            .map(|(entity, _)| *entity)
+            .collect()
+    };
+
+    for (player, command) in due {
+        match command {
+            PlayerCommand::Move { units, target } => {
+                for unit in owned_by(player, &units) {
+                    commands
+                        .entity(unit)
+                        .remove::<(Attack, AutoOrder, Gather)>()
+                        .insert(MoveTo { target });
+                }
+            }
+            PlayerCommand::Attack { units, target } => {
+                let Some((target, _)) = entities.get(&target) else {
+                    continue;
+                };
+                for unit in owned_by(player, &units) {
+                    commands
+                        .entity(unit)
+                        .remove::<(MoveTo, AutoOrder, Gather)>()
+                        .insert(Attack {
+                            target: *target,
+                            hold: false,
+                        });
+                }
+            }
+            PlayerCommand::Train { unit } => {
+                train_events.write(TrainUnit { player, unit });
+            }
+            PlayerCommand::Gather { units, node } => {
+                let Some(node) = node_near(
+                    nodes_query
+                        .iter()
+                        .map(|transform| transform.translation.xz()),
+                    node,
+                    economy_config.gather_reach,
+                ) else {
+                    continue;
+                };
+                for unit in owned_by(player, &units) {
+                    commands
+                        .entity(unit)
+                        .remove::<(MoveTo, Attack, AutoOrder)>()
+                        .insert(Gather::new(node));
+                }
+            }
+            PlayerCommand::CastAbility {
+                caster,
+                ability,
+                target,
+            } => {
+                let Some(caster) = owned_by(player, &[caster]).pop() else {
+                    continue;
+                };
+                // Passives trigger on their own, a player can't cast them at will
+                let castable = abilities_query.get(caster).is_ok_and(|abilities| {
+                    abilities
+                        .0
+                        .get(ability)
+                        .is_some_and(|ability| ability.target != AbilityTarget::Passive)
+                });
+                if !castable {
+                    continue;
+                }
+                let target = match target {
+                    CommandTarget::Point(point) => CastTarget::Point(point),
+                    CommandTarget::Unit(id) => match entities.get(&id) {
+                        Some((target, _)) => CastTarget::Unit(*target),
+                        None => continue,
+                    },
+                    CommandTarget::Caster => CastTarget::Caster,
+                };
+                casts.write(CastAbility {
+                    caster,
+                    ability,
+                    target,
+                });
+            }
+            PlayerCommand::CycleStance { units } => {
+                for unit in owned_by(player, &units) {
+                    if let Ok(mut stance) = stances_query.get_mut(unit) {
+                        *stance = stance.next();
+                    }
+                }
+            }
+            PlayerCommand::Stop { units } => {
+                for unit in owned_by(player, &units) {
+                    commands
+                        .entity(unit)
+                        .remove::<(MoveTo, Attack, AutoOrder, Gather)>();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::{env, path::PathBuf};
+
+const APP_DIR: &str = "rts-game-rs";
+
+/// Per-user directory where the game keeps its data (e.g. save games).
+///
+/// Follows the XDG convention on Unix and `%APPDATA%` on Windows, falling back to the current
+/// directory when none of them is available.
+pub fn data_dir() -> PathBuf {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .unwrap_or_default()
+        .join(APP_DIR)
+}
+
+/// Per-user directory where the game keeps its settings, same lookup as [`data_dir`].
+pub fn config_dir() -> PathBuf {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_default()
+        .join(APP_DIR)
+}
@@ -0,0 +1,214 @@
+use bevy::{
+    picking::{
+        PickSet,
+        backend::{HitData, PointerHits, ray::RayMap},
+    },
+    prelude::*,
+};
+
+use crate::{camera::MainCamera, config::picking::PickingConfig};
+
+/// Added to the order of the main camera for unit hits, which puts them above the terrain but
+/// under the interface, picked at half an order above its camera.
+pub const UNIT_ORDER_OFFSET: f32 = 0.25;
+
+/// Picks units and the terrain by casting rays against their [`PickShape`]. Units are always
+/// picked over the terrain they stand on, and only the one closest to the pointer is hit.
+pub struct PickingBackendPlugin;
+
+impl Plugin for PickingBackendPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, cast_pointer_rays.in_set(PickSet::Backend));
+
+        // Apps running the backend without a renderer have nothing to draw gizmos with
+        #[cfg(feature = "picking-debug")]
+        if app.is_plugin_added::<bevy::gizmos::GizmoPlugin>() {
+            app.add_systems(Update, draw_pick_shapes);
+        }
+    }
+}
+
+/// What rays are cast against, in place of a collider.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum PickShape {
+    /// Upright capsule centred on a unit
+    Unit { radius: f32, half_height: f32 },
+    /// Flat rectangle centred on the terrain
+    Ground { half_size: Vec2 },
+}
+
+impl PickShape {
+    /// Distance along `ray` to where it gets closest to the shape, and how far it passes from the
+    /// surface of a unit, negative inside it. `pick_radius` widens units.
+    pub fn cast(&self, ray: Ray3d, center: Vec3, pick_radius: f32) -> Option<(f32, f32)> {
+        match *self {
+            PickShape::Unit {
+                radius,
+                half_height,
+            } => {
+                let bottom = center - Vec3::Y * half_height;
+                let (depth, distance) =
+                    ray_segment_closest(ray, bottom, Vec3::Y * half_height * 2.);
+                let margin = distance - radius;
+                (margin <= pick_radius).then_some((depth, margin))
+            }
+            PickShape::Ground { half_size } => {
+                let depth = ray.intersect_plane(center, InfinitePlane3d::new(Vec3::Y))?;
+                let offset = (ray.get_point(depth) - center).xz().abs();
+                offset.cmple(half_size).all().then_some((depth, 0.))
+            }
+        }
+    }
+}
+
+/// Distance along `ray` to where it comes closest to the segment from `start` to
+/// `start + extent`, and that closest distance.
+fn ray_segment_closest(ray: Ray3d, start: Vec3, extent: Vec3) -> (f32, f32) {
+    let direction = *ray.direction;
+    let offset = ray.origin - start;
+    let length_squared = extent.length_squared();
+    let alignment = direction.dot(extent);
+    let along_ray = direction.dot(offset);
+    let along_segment = extent.dot(offset);
+
+    // Closest points of the two lines, then clamped to the ray and the segment
+    let denominator = length_squared - alignment * alignment;
+    let mut depth = if denominator > f32::EPSILON {
+        ((alignment * along_segment - along_ray * length_squared) / denominator).max(0.)
+    } else {
+        0.
+    };
+    let mut fraction = if length_squared > f32::EPSILON {
+        (alignment * depth + along_segment) / length_squared
+    } else {
+        0.
+    };
+    if fraction < 0. {
+        fraction = 0.;
+        depth = (-along_ray).max(0.);
+    } else if fraction > 1. {
+        fraction = 1.;
+        depth = (alignment - along_ray).max(0.);
+    }
+
+    let distance = ray.get_point(depth).distance(start + extent * fraction);
+    (depth, distance)
+}
+
+/// The best unit and ground hits of a ray, each as the entity and the distance along the ray.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Picks {
+    pub unit: Option<(Entity, f32)>,
+    pub ground: Option<(Entity, f32)>,
+}
+
+/// Casts `ray` against every shape. In a crowd the unit the ray passes closest to wins, rather
+/// than the one nearest the camera. Needs neither a window nor physics, so tests can use it.
+pub fn pick<'a>(
+    ray: Ray3d,
+    shapes: impl IntoIterator<Item = (Entity, &'a PickShape, Vec3)>,
+    pick_radius: f32,
+) -> Picks {
+    let mut picks = Picks::default();
+    let mut best_margin = f32::INFINITY;
+
+    for (entity, shape, center) in shapes {
+        let Some((depth, margin)) = shape.cast(ray, center, pick_radius) else {
+            continue;
+        };
+
+        match shape {
+            PickShape::Unit { .. } => {
+                if margin < best_margin {
+                    best_margin = margin;
+                    picks.unit = Some((entity, depth));
+                }
+            }
+            PickShape::Ground { .. } => {
+                if picks.ground.is_none_or(|(_, closest)| depth < closest) {
+                    picks.ground = Some((entity, depth));
+                }
+            }
+        }
+    }
+
+    picks
+}
+
+// Units and the terrain are reported apart, units at a higher order so that they block the
+// terrain behind them like the interface blocks everything.
+fn cast_pointer_rays(
+    ray_map: Res<RayMap>,
+    cameras_query: Query<&Camera, With<MainCamera>>,
+    shapes_query: Query<(Entity, &PickShape, &GlobalTransform, &InheritedVisibility)>,
+    picking_config: Res<PickingConfig>,
+    mut output: EventWriter<PointerHits>,
+) {
+    for (ray_id, ray) in ray_map.iter() {
+        let Ok(camera) = cameras_query.get(ray_id.camera) else {
+            continue;
+        };
+
+        // Hidden entities cannot be clicked. Nothing hides units yet, as there is no fog of war
+        let shapes = shapes_query
+            .iter()
+            .filter(|(.., visibility)| visibility.get())
+            .map(|(entity, shape, transform, _)| (entity, shape, transform.translation()));
+        let picks = pick(*ray, shapes, picking_config.unit_pick_radius);
+
+        let hit = |(entity, depth): (Entity, f32), normal: Option<Vec3>| {
+            (
+                entity,
+                HitData::new(ray_id.camera, depth, Some(ray.get_point(depth)), normal),
+            )
+        };
+        let order = camera.order as f32;
+
+        if let Some(unit) = picks.unit {
+            output.write(PointerHits::new(
+                ray_id.pointer,
+                vec![hit(unit, None)],
+                order + UNIT_ORDER_OFFSET,
+            ));
+        }
+        if let Some(ground) = picks.ground {
+            output.write(PointerHits::new(
+                ray_id.pointer,
+                vec![hit(ground, Some(Vec3::Y))],
+                order,
+            ));
+        }
+    }
+}
+
+#[cfg(feature = "picking-debug")]
+fn draw_pick_shapes(
+    mut gizmos: Gizmos,
+    shapes_query: Query<(&PickShape, &GlobalTransform)>,
+    picking_config: Res<PickingConfig>,
+) {
+    const COLOR: Color = Color::srgb(1., 0., 1.);
+
+    for (shape, transform) in shapes_query.iter() {
+        let center = transform.translation();
+        match *shape {
+            PickShape::Unit {
+                radius,
+                half_height,
+            } => {
+                gizmos.primitive_3d(
+                    &Capsule3d::new(radius + picking_config.unit_pick_radius, half_height * 2.),
+                    Isometry3d::from_translation(center),
+                    COLOR,
+                );
+            }
+            PickShape::Ground { half_size } => {
+                gizmos.rect(
+                    Isometry3d::new(center, Quat::from_rotation_arc(Vec3::Z, Vec3::Y)),
+                    half_size * 2.,
+                    COLOR,
+                );
+            }
+        }
+    }
+}
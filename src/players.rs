@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_states::GameState,
+    match_settings::{MatchSettings, PlayerColor, SlotKind},
+    save::LoadedSave,
+};
+
+pub struct PlayersPlugin;
+
+impl Plugin for PlayersPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Player>()
+            .register_type::<PlayerStats>()
+            .register_type::<Owner>()
+            .register_type::<LocalPlayer>()
+            .init_resource::<LocalPlayer>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                spawn_players.run_if(not(resource_exists::<LoadedSave>)),
+            );
+    }
+}
+
+/// Index of the player's slot in [`MatchSettings::slots`].
+#[derive(
+    Reflect,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub struct PlayerId(pub u8);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(PlayerStats)]
+pub struct Player {
+    pub id: PlayerId,
+    pub kind: SlotKind,
+    pub team: u8,
+    pub color: PlayerColor,
+    pub resources: u32,
+}
+
+/// What a player did during the match, shown once it is over.
+#[derive(Component, Reflect, Debug, Clone, Default, Serialize)]
+#[reflect(Component)]
+pub struct PlayerStats {
+    /// Units trained during the match, the starting ones are not counted
+    pub units_produced: u32,
+    pub units_lost: u32,
+    pub units_killed: u32,
+    pub resources_gathered: u32,
+}
+
+/// The player controlling a unit.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct Owner(pub PlayerId);
+
+/// The player using this machine, whose units can be selected and commanded.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct LocalPlayer(pub PlayerId);
+
+/// Spawns a [`Player`] for every open slot, units are spawned after it.
+pub fn spawn_players(
+    mut commands: Commands,
+    match_settings: Res<MatchSettings>,
+    mut local_player: ResMut<LocalPlayer>,
+) {
+    for (index, slot) in match_settings.open_slots() {
+        let id = PlayerId(index as u8);
+
+        commands.spawn((
+            Name::new(format!("Player {}", index + 1)),
+            StateScoped(GameState::Playing),
+            Player {
+                id,
+                kind: slot.kind,
+                team: slot.team,
+                color: slot.color,
+                resources: match_settings.starting_resources,
+            },
+        ));
+    }
+
+    if let Some((index, _)) = match_settings
+        .open_slots()
+        .find(|(_, slot)| slot.kind == SlotKind::Human)
+    {
+        local_player.0 = PlayerId(index as u8);
+    }
+}
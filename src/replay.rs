@@ -0,0 +1,338 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::bail;
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_states::GameState,
+    input::Action,
+    match_settings::MatchSettings,
+    orders::{
+        LocalCommands, PlayerCommand, ScheduledCommands, UnitId, apply_commands, reset_orders,
+    },
+    paths::data_dir,
+    players::{Player, PlayerId},
+    save::LoadedSave,
+    simulation::{SimulationSet, SimulationTick, advance_tick, state_checksum, tick_gate_open},
+    units::health::Health,
+};
+
+pub const REPLAY_EXTENSION: &str = "replay.json";
+/// Bumped whenever the format or the simulation changes in a way that breaks older replays.
+pub const REPLAY_VERSION: u32 = 3;
+/// Playback speeds the viewer steps through.
+const PLAYBACK_SPEEDS: [f32; 6] = [0.25, 0.5, 1., 2., 4., 8.];
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            (
+                start_recording.run_if(
+                    not(resource_exists::<LoadedSave>).and(not(resource_exists::<ReplayPlayback>)),
+                ),
+                (start_playback.after(reset_orders), spawn_playback_status)
+                    .run_if(resource_exists::<ReplayPlayback>),
+            ),
+        )
+        .add_systems(OnExit(GameState::Playing), (save_recording, stop_playback))
+        .add_systems(
+            FixedFirst,
+            discard_local_commands
+                .run_if(in_state(GameState::Playing).and(resource_exists::<ReplayPlayback>)),
+        )
+        .add_systems(
+            FixedUpdate,
+            record_commands
+                .before(apply_commands)
+                .in_set(SimulationSet::Commands)
+                .run_if(resource_exists::<ReplayRecorder>),
+        )
+        .add_systems(
+            FixedLast,
+            (
+                record_checksum.run_if(resource_exists::<ReplayRecorder>),
+                check_playback.run_if(resource_exists::<ReplayPlayback>),
+            )
+                .after(advance_tick)
+                .run_if(in_state(GameState::Playing).and(tick_gate_open)),
+        )
+        .add_systems(
+            Update,
+            (
+                toggle_pause.run_if(input_just_pressed(Action::ReplayPause)),
+                change_speed(-1).run_if(input_just_pressed(Action::ReplaySlower)),
+                change_speed(1).run_if(input_just_pressed(Action::ReplayFaster)),
+                update_playback_status,
+            )
+                .chain()
+                .run_if(in_state(GameState::Playing).and(resource_exists::<ReplayPlayback>)),
+        );
+    }
+}
+
+/// A command as it was played during a match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCommand {
+    pub tick: u64,
+    pub player: PlayerId,
+    pub command: PlayerCommand,
+}
+
+/// Everything needed to play a match again: the settings it was started with, including the map
+/// and seed, and the commands of every player. Selecting units doesn't change the simulation, the
+/// units a command was given to are part of the command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub settings: MatchSettings,
+    pub commands: Vec<RecordedCommand>,
+    /// Ticks simulated before the recording stopped
+    pub ticks: u64,
+    /// [`state_checksum`] after the last tick, which a correct playback ends on
+    pub checksum: u64,
+}
+
+impl Replay {
+    pub fn new(settings: MatchSettings) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            settings,
+            commands: Vec::new(),
+            ticks: 0,
+            checksum: 0,
+        }
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let replay: Replay = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if replay.version != REPLAY_VERSION {
+            bail!(
+                "Replay version {} can't be played, expected {REPLAY_VERSION}",
+                replay.version
+            );
+        }
+
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// The commands ordered by the tick they are played at.
+    pub fn schedule(&self) -> ScheduledCommands {
+        let mut scheduled = ScheduledCommands::default();
+        for recorded in self.commands.iter() {
+            scheduled
+                .0
+                .entry(recorded.tick)
+                .or_default()
+                .push((recorded.player, recorded.command.clone()));
+        }
+
+        scheduled
+    }
+}
+
+/// Present for the whole match when it is a recording being watched, in which case commands
+/// come from the replay instead of the input.
+#[derive(Resource, Debug)]
+pub struct ReplayPlayback(pub Replay);
+
+/// The replay of the match being played, written to disk when it ends.
+#[derive(Resource, Debug)]
+pub struct ReplayRecorder(pub Replay);
+
+/// A replay file found on disk.
+#[derive(Debug)]
+pub struct ReplaySlot {
+    pub name: String,
+    pub path: PathBuf,
+    pub modified: SystemTime,
+}
+
+pub fn replay_dir() -> PathBuf {
+    data_dir().join("replays")
+}
+
+/// Lists the replay files, most recent first.
+pub fn list_replays() -> Vec<ReplaySlot> {
+    let Ok(entries) = fs::read_dir(replay_dir()) else {
+        return Vec::new();
+    };
+
+    let mut slots: Vec<ReplaySlot> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path
+                .file_name()?
+                .to_str()?
+                .strip_suffix(&format!(".{REPLAY_EXTENSION}"))?
+                .to_string();
+            let modified = entry.metadata().ok()?.modified().ok()?;
+
+            Some(ReplaySlot {
+                name,
+                path,
+                modified,
+            })
+        })
+        .collect();
+    slots.sort_by_key(|slot| Reverse(slot.modified));
+
+    slots
+}
+
+#[derive(Component)]
+struct PlaybackStatus;
+
+fn start_recording(mut commands: Commands, match_settings: Res<MatchSettings>) {
+    commands.insert_resource(ReplayRecorder(Replay::new(match_settings.clone())));
+}
+
+fn record_commands(
+    mut recorder: ResMut<ReplayRecorder>,
+    scheduled: Res<ScheduledCommands>,
+    tick: Res<SimulationTick>,
+) {
+    // Same selection as `apply_commands`, which plays them right after
+    let Some(due) = scheduled.0.get(&tick.0) else {
+        return;
+    };
+    recorder
+        .0
+        .commands
+        .extend(due.iter().map(|(player, command)| RecordedCommand {
+            tick: tick.0,
+            player: *player,
+            command: command.clone(),
+        }));
+}
+
+fn record_checksum(
+    mut recorder: ResMut<ReplayRecorder>,
+    tick: Res<SimulationTick>,
+    units_query: Query<(&UnitId, &Transform, &Health)>,
+    players_query: Query<&Player>,
+) {
+    recorder.0.ticks = tick.0;
+    recorder.0.checksum = state_checksum(units_query.iter(), players_query.iter());
+}
+
+fn save_recording(mut commands: Commands, recorder: Option<Res<ReplayRecorder>>) {
+    let Some(recorder) = recorder else {
+        return;
+    };
+    commands.remove_resource::<ReplayRecorder>();
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let path = replay_dir().join(format!("replay_{timestamp}.{REPLAY_EXTENSION}"));
+
+    match recorder.0.save(&path) {
+        Ok(()) => info!("Replay saved to {}", path.display()),
+        Err(error) => error!("Could not save the replay to {}: {error}", path.display()),
+    }
+}
+
+fn start_playback(mut commands: Commands, playback: Res<ReplayPlayback>) {
+    commands.insert_resource(playback.0.schedule());
+}
+
+fn discard_local_commands(mut local_commands: ResMut<LocalCommands>) {
+    local_commands.0.clear();
+}
+
+fn check_playback(
+    playback: Res<ReplayPlayback>,
+    tick: Res<SimulationTick>,
+    units_query: Query<(&UnitId, &Transform, &Health)>,
+    players_query: Query<&Player>,
+) {
+    if tick.0 != playback.0.ticks {
+        return;
+    }
+
+    if state_checksum(units_query.iter(), players_query.iter()) == playback.0.checksum {
+        info!("Replay played back to tick {} as recorded", tick.0);
+    } else {
+        error!("Replay diverged from the recording by tick {}", tick.0);
+    }
+}
+
+fn stop_playback(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    commands.remove_resource::<ReplayPlayback>();
+    time.unpause();
+    time.set_relative_speed(1.);
+}
+
+fn toggle_pause(mut time: ResMut<Time<Virtual>>) {
+    if time.is_paused() {
+        time.unpause();
+    } else {
+        time.pause();
+    }
+}
+
+fn change_speed(step: i32) -> impl FnMut(ResMut<Time<Virtual>>) {
+    move |mut time: ResMut<Time<Virtual>>| {
+        let current = PLAYBACK_SPEEDS
+            .iter()
+            .position(|speed| *speed >= time.relative_speed())
+            .unwrap_or(PLAYBACK_SPEEDS.len() - 1) as i32;
+        let next = (current + step).clamp(0, PLAYBACK_SPEEDS.len() as i32 - 1);
+        time.set_relative_speed(PLAYBACK_SPEEDS[next as usize]);
+    }
+}
+
+fn spawn_playback_status(mut commands: Commands) {
+    commands.spawn((
+        Name::new("PlaybackStatus"),
+        StateScoped(GameState::Playing),
+        PlaybackStatus,
+        Text::default(),
+        TextFont {
+            font_size: 22.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            right: Val::Px(10.),
+            ..default()
+        },
+    ));
+}
+
+fn update_playback_status(
+    mut status_query: Query<&mut Text, With<PlaybackStatus>>,
+    time: Res<Time<Virtual>>,
+    tick: Res<SimulationTick>,
+    playback: Res<ReplayPlayback>,
+) {
+    let state = if time.is_paused() {
+        "Paused".to_string()
+    } else {
+        format!("x{}", time.relative_speed())
+    };
+
+    for mut text in status_query.iter_mut() {
+        text.0 = format!("Replay {state} - tick {}/{}", tick.0, playback.0.ticks);
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    ecs::entity::EntityHashMap, input::common_conditions::input_just_pressed, prelude::*,
+    scene::serde::SceneDeserializer,
+};
+use serde::de::DeserializeSeed;
+
+use crate::{
+    ai::AiController,
+    buildings::{Building, attach_base},
+    camera::MainCamera,
+    economy::Gather,
+    game_states::GameState,
+    input::Action,
+    match_settings::MatchSettings,
+    orders::{NextUnitId, UnitId},
+    paths::data_dir,
+    players::{LocalPlayer, Owner, Player, PlayerStats},
+    simulation::{SimulationRng, SimulationTick},
+    units::{
+        MoveTo, Movement, SOLDIER_BODY, Selected, Unit,
+        combat::{Attack, Weapon},
+        health::Health,
+        hero::{Abilities, HERO_BODY, Hero, Inventory, Mana},
+        rendering::UnitAssets,
+        tactics::{Anchor, AutoOrder, Stance, Threat},
+    },
+    victory::MatchProgress,
+    weather::WeatherState,
+};
+
+pub const SAVE_EXTENSION: &str = "scn.ron";
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            load_game.run_if(resource_exists::<LoadedSave>),
+        )
+        .add_systems(OnExit(GameState::Playing), forget_loaded_save)
+        .add_systems(
+            Update,
+            (
+                save_game.run_if(input_just_pressed(Action::QuickSave)),
+                restore_players,
+                restore_units,
+                restore_buildings,
+                restore_camera,
+            )
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Present for the whole match when it has been started from a save file rather than from
+/// scratch, in which case the usual spawning of units is skipped.
+#[derive(Resource, Debug)]
+pub struct LoadedSave(pub PathBuf);
+
+/// A save file found on disk.
+#[derive(Debug)]
+pub struct SaveSlot {
+    pub name: String,
+    pub path: PathBuf,
+    pub modified: SystemTime,
+}
+
+pub fn save_dir() -> PathBuf {
+    data_dir().join("saves")
+}
+
+/// Lists the save files, most recent first.
+pub fn list_saves() -> Vec<SaveSlot> {
+    let Ok(entries) = fs::read_dir(save_dir()) else {
+        return Vec::new();
+    };
+
+    let mut slots: Vec<SaveSlot> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path
+                .file_name()?
+                .to_str()?
+                .strip_suffix(&format!(".{SAVE_EXTENSION}"))?
+                .to_string();
+            let modified = entry.metadata().ok()?.modified().ok()?;
+
+            Some(SaveSlot {
+                name,
+                path,
+                modified,
+            })
+        })
+        .collect();
+    slots.sort_by_key(|slot| Reverse(slot.modified));
+
+    slots
+}
+
+fn save_game(world: &mut World) -> Result {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let path = save_dir().join(format!("save_{timestamp}.{SAVE_EXTENSION}"));
+    write_save(world, &path)?;
+
+    info!("Game saved to {}", path.display());
+
+    Ok(())
+}
+
+// Only gameplay state is saved: meshes, materials, pick shapes and observers are rebuilt on load by
+// `restore_units` and `restore_buildings`, and the camera is moved back to where it was by
+// `restore_camera`.
+/// Writes the match to `path`, to be played on by starting a match with [`LoadedSave`].
+pub fn write_save(world: &mut World, path: &Path) -> Result {
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(With<Unit>, With<Building>, With<Player>, With<MainCamera>)>>(
+        )
+        .iter(world)
+        .collect();
+
+    let scene = DynamicSceneBuilder::from_world(world)
+        .deny_all()
+        .allow_component::<Name>()
+        .allow_component::<Transform>()
+        .allow_component::<MainCamera>()
+        .allow_component::<Projection>()
+        .allow_component::<Player>()
+        .allow_component::<PlayerStats>()
+        .allow_component::<AiController>()
+        .allow_component::<Owner>()
+        .allow_component::<Unit>()
+        .allow_component::<Building>()
+        .allow_component::<UnitId>()
+        .allow_component::<Selected>()
+        .allow_component::<Movement>()
+        .allow_component::<MoveTo>()
+        .allow_component::<Health>()
+        .allow_component::<Weapon>()
+        .allow_component::<Attack>()
+        .allow_component::<Stance>()
+        .allow_component::<Anchor>()
+        .allow_component::<AutoOrder>()
+        .allow_component::<Threat>()
+        .allow_component::<Gather>()
+        .allow_component::<Hero>()
+        .allow_component::<Mana>()
+        .allow_component::<Inventory>()
+        .allow_component::<Abilities>()
+        .allow_resource::<MatchSettings>()
+        .allow_resource::<LocalPlayer>()
+        .allow_resource::<MatchProgress>()
+        .allow_resource::<SimulationTick>()
+        .allow_resource::<SimulationRng>()
+        .allow_resource::<NextUnitId>()
+        .allow_resource::<WeatherState>()
+        .extract_entities(entities.into_iter())
+        .extract_resources()
+        .build();
+
+    let serialized = scene.serialize(&world.resource::<AppTypeRegistry>().read())?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serialized)?;
+
+    Ok(())
+}
+
+fn load_game(world: &mut World) -> Result {
+    let path = world.resource::<LoadedSave>().0.clone();
+    let contents = fs::read_to_string(&path)?;
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let scene = SceneDeserializer {
+        type_registry: &type_registry.read(),
+    }
+    .deserialize(&mut ron::de::Deserializer::from_str(&contents)?)?;
+
+    scene.write_to_world(world, &mut EntityHashMap::default())?;
+
+    info!("Game loaded from {}", path.display());
+
+    Ok(())
+}
+
+fn forget_loaded_save(mut commands: Commands) {
+    commands.remove_resource::<LoadedSave>();
+}
+
+fn restore_players(
+    mut commands: Commands,
+    players_query: Query<Entity, (Added<Player>, Without<StateScoped<GameState>>)>,
+) {
+    for entity in players_query.iter() {
+        commands
+            .entity(entity)
+            .insert(StateScoped(GameState::Playing));
+    }
+}
+
+fn restore_units(
+    mut commands: Commands,
+    mut assets: UnitAssets,
+    units_query: Query<(Entity, &Owner, Has<Hero>, Has<Selected>), (Added<Unit>, Without<Mesh3d>)>,
+    players_query: Query<&Player>,
+) {
+    for (entity, owner, is_hero, selected) in units_query.iter() {
+        let body = if is_hero { &HERO_BODY } else { &SOLDIER_BODY };
+        let color = players_query
+            .iter()
+            .find(|player| player.id == owner.0)
+            .map_or(Color::WHITE, |player| player.color.color());
+
+        body.attach(&mut commands.entity(entity), &mut assets, color, selected);
+    }
+}
+
+fn restore_buildings(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    buildings_query: Query<(Entity, &Owner), (Added<Building>, Without<Mesh3d>)>,
+    players_query: Query<&Player>,
+) {
+    for (entity, owner) in buildings_query.iter() {
+        let color = players_query
+            .iter()
+            .find(|player| player.id == owner.0)
+            .map_or(Color::WHITE, |player| player.color.color());
+
+        attach_base(
+            &mut commands.entity(entity),
+            &mut meshes,
+            &mut materials,
+            color,
+        );
+    }
+}
+
+fn restore_camera(
+    mut commands: Commands,
+    saved_camera_query: Query<
+        (Entity, &Transform, &Projection),
+        (With<MainCamera>, Without<Camera3d>),
+    >,
+    camera_query: Single<(&mut Transform, &mut Projection), With<Camera3d>>,
+) {
+    let (mut transform, mut projection) = camera_query.into_inner();
+
+    for (entity, saved_transform, saved_projection) in saved_camera_query.iter() {
+        *transform = *saved_transform;
+        *projection = saved_projection.clone();
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,264 @@
+use bevy::{app::RunFixedMainLoopSystem, prelude::*};
+
+use crate::{
+    game_states::GameState,
+    match_settings::MatchSettings,
+    orders::UnitId,
+    players::Player,
+    save::LoadedSave,
+    units::{Unit, health::Health},
+};
+
+/// Rate at which the simulation advances, independently of the frame rate.
+pub const TICKS_PER_SECOND: f64 = 30.;
+
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SimulationTick>()
+            .register_type::<SimulationRng>()
+            .register_type::<InterpolatedTranslation>()
+            .insert_resource(Time::<Fixed>::from_hz(TICKS_PER_SECOND))
+            .init_resource::<SimulationTick>()
+            .init_resource::<SimulationRng>()
+            .init_resource::<TickGate>()
+            .configure_sets(
+                FixedUpdate,
+                (
+                    SimulationSet::Index,
+                    SimulationSet::Environment,
+                    SimulationSet::Commands,
+                    SimulationSet::Ai,
+                    SimulationSet::Tactics,
+                    SimulationSet::Abilities,
+                    SimulationSet::Attacks,
+                    SimulationSet::Damage,
+                    SimulationSet::Casualties,
+                    SimulationSet::Movement,
+                    SimulationSet::Economy,
+                    SimulationSet::Victory,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing).and(tick_gate_open)),
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (
+                    open_tick_gate,
+                    start_simulation.run_if(not(resource_exists::<LoadedSave>)),
+                ),
+            )
+            .add_systems(
+                FixedFirst,
+                (track_units, restore_translations).run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedLast,
+                (store_translations, advance_tick)
+                    .run_if(in_state(GameState::Playing).and(tick_gate_open)),
+            )
+            .add_systems(
+                RunFixedMainLoop,
+                interpolate_translations
+                    .in_set(RunFixedMainLoopSystem::AfterFixedMainLoop)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Steps of a simulation tick, run in this order in [`FixedUpdate`]. Gameplay systems must be
+/// in one of them so that every machine runs them in the same order.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimulationSet {
+    /// The [`crate::units::spatial::SpatialIndex`] catches up with units moved since the last tick
+    Index,
+    /// The weather changes, before anything depends on it
+    Environment,
+    /// Commands of every player scheduled for this tick are carried out
+    Commands,
+    /// Computer players give their orders
+    Ai,
+    /// Units without orders decide what to do on their own
+    Tactics,
+    /// Heroes cast their abilities
+    Abilities,
+    /// Weapons are fired at the targets of [`crate::units::combat::Attack`] orders
+    Attacks,
+    /// Damage is applied and dead units are removed
+    Damage,
+    /// Deaths are credited to their killers and counted in the statistics
+    Casualties,
+    Movement,
+    Economy,
+    /// Victory conditions are checked against the outcome of the tick
+    Victory,
+}
+
+/// Closed while the simulation has to wait before running the next tick, typically for the
+/// commands of other players to arrive.
+#[derive(Resource, Debug)]
+pub struct TickGate {
+    pub open: bool,
+}
+
+impl Default for TickGate {
+    fn default() -> Self {
+        Self { open: true }
+    }
+}
+
+pub fn tick_gate_open(gate: Res<TickGate>) -> bool {
+    gate.open
+}
+
+/// Number of simulation ticks since the match started.
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct SimulationTick(pub u64);
+
+/// The only source of randomness gameplay code may use, seeded from [`MatchSettings::seed`] so
+/// that the same match always plays out the same way.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct SimulationRng {
+    state: u64,
+}
+
+impl Default for SimulationRng {
+    fn default() -> Self {
+        Self::from_seed(0)
+    }
+}
+
+impl SimulationRng {
+    pub fn from_seed(seed: u64) -> Self {
+        const MIXER: u64 = 0x9E37_79B9_7F4A_7C15;
+
+        // A zero state would only ever produce zeros
+        let state = seed ^ MIXER;
+        Self {
+            state: if state == 0 { MIXER } else { state },
+        }
+    }
+
+    /// xorshift64*, fast and good enough for gameplay.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform in `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `0..upper`, `upper` must not be zero.
+    pub fn below(&mut self, upper: u32) -> u32 {
+        (self.next_u64() % upper as u64) as u32
+    }
+}
+
+/// Where a unit stood at the last two ticks. The simulation works on [`Transform`], which is
+/// moved in between ticks to smooth out rendering and put back before the next tick. It is only
+/// marked as changed when `current` changes, that is when the unit moved during the last tick.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct InterpolatedTranslation {
+    pub previous: Vec3,
+    pub current: Vec3,
+}
+
+/// FNV-1a over the state that matters to the outcome of a match, equal on every machine playing
+/// the same match.
+pub fn state_checksum<'a>(
+    units: impl Iterator<Item = (&'a UnitId, &'a Transform, &'a Health)>,
+    players: impl Iterator<Item = &'a Player>,
+) -> u64 {
+    const OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01B3;
+
+    let mut hash = OFFSET;
+    let mut write = |value: u64| {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+
+    let mut units: Vec<_> = units.collect();
+    units.sort_by_key(|(id, ..)| **id);
+    for (id, transform, health) in units {
+        write(id.0 as u64);
+        write(transform.translation.x.to_bits() as u64);
+        write(transform.translation.z.to_bits() as u64);
+        write(health.current.to_bits() as u64);
+    }
+
+    let mut players: Vec<_> = players.collect();
+    players.sort_by_key(|player| player.id);
+    for player in players {
+        write(player.id.0 as u64);
+        write(player.resources as u64);
+    }
+
+    hash
+}
+
+fn start_simulation(mut commands: Commands, match_settings: Res<MatchSettings>) {
+    commands.insert_resource(SimulationTick::default());
+    commands.insert_resource(SimulationRng::from_seed(match_settings.seed));
+}
+
+/// Opened at the start of every match, loaded ones included, as a lockstep match stopped while
+/// waiting for the other players leaves it closed.
+fn open_tick_gate(mut commands: Commands) {
+    commands.insert_resource(TickGate::default());
+}
+
+fn track_units(
+    mut commands: Commands,
+    units_query: Query<(Entity, &Transform), (With<Unit>, Without<InterpolatedTranslation>)>,
+) {
+    for (entity, transform) in units_query.iter() {
+        commands.entity(entity).insert(InterpolatedTranslation {
+            previous: transform.translation,
+            current: transform.translation,
+        });
+    }
+}
+
+pub fn restore_translations(
+    mut units_query: Query<(&mut Transform, &mut InterpolatedTranslation)>,
+) {
+    for (mut transform, mut interpolated) in units_query.iter_mut() {
+        transform.translation = interpolated.current;
+        let interpolated = interpolated.bypass_change_detection();
+        interpolated.previous = interpolated.current;
+    }
+}
+
+fn store_translations(mut units_query: Query<(&Transform, &mut InterpolatedTranslation)>) {
+    for (transform, mut interpolated) in units_query.iter_mut() {
+        if interpolated.current != transform.translation {
+            interpolated.current = transform.translation;
+        }
+    }
+}
+
+pub fn advance_tick(mut tick: ResMut<SimulationTick>) {
+    tick.0 += 1;
+}
+
+fn interpolate_translations(
+    mut units_query: Query<(&mut Transform, &InterpolatedTranslation)>,
+    fixed_time: Res<Time<Fixed>>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+
+    for (mut transform, interpolated) in units_query.iter_mut() {
+        transform.translation = interpolated.previous.lerp(interpolated.current, alpha);
+    }
+}
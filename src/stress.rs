@@ -0,0 +1,106 @@
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+
+use crate::{
+    game_states::GameState,
+    match_settings::MatchSettings,
+    players::{Player, spawn_players},
+    save::LoadedSave,
+    units::{rendering::UnitAssets, spawn_soldier, tactics::Stance},
+};
+
+/// Widest gap between two units of the stress scene, smaller on maps too small for it.
+const MAX_SPACING: f32 = 0.3;
+/// Time between two frame time reports.
+const REPORT_INTERVAL: f32 = 5.;
+
+/// Fills the match with soldiers standing still in a grid, shared between the players, and logs
+/// the frame time to measure how rendering copes with many units. Started with `--stress`.
+pub struct StressTestPlugin {
+    pub units: usize,
+}
+
+impl Plugin for StressTestPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+
+        app.insert_resource(StressTest {
+            units: self.units,
+            report: Timer::from_seconds(REPORT_INTERVAL, TimerMode::Repeating),
+        })
+        .add_systems(
+            OnEnter(GameState::Playing),
+            spawn_units
+                .after(spawn_players)
+                .run_if(not(resource_exists::<LoadedSave>)),
+        )
+        .add_systems(
+            Update,
+            report_frame_time.run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct StressTest {
+    units: usize,
+    report: Timer,
+}
+
+fn spawn_units(
+    mut commands: Commands,
+    mut assets: UnitAssets,
+    players_query: Query<&Player>,
+    stress_test: Res<StressTest>,
+    match_settings: Res<MatchSettings>,
+) {
+    let players: Vec<&Player> = players_query.iter().collect();
+    if players.is_empty() {
+        return;
+    }
+
+    let columns = (stress_test.units as f32).sqrt().ceil().max(1.);
+    let half_size = match_settings.map.half_size.min_element();
+    let spacing = (2. * half_size / columns).min(MAX_SPACING);
+    let origin = -Vec2::splat(spacing * (columns - 1.) / 2.);
+
+    for index in 0..stress_test.units {
+        let cell = Vec2::new(
+            (index % columns as usize) as f32,
+            (index / columns as usize) as f32,
+        );
+        let player = players[index % players.len()];
+
+        let unit = spawn_soldier(&mut commands, &mut assets, player, origin + cell * spacing);
+        // Fights would thin out the crowd being measured
+        commands.entity(unit).insert(Stance::Passive);
+    }
+
+    info!("Stress test: spawned {} units", stress_test.units);
+}
+
+fn report_frame_time(
+    mut stress_test: ResMut<StressTest>,
+    diagnostics: Res<DiagnosticsStore>,
+    time: Res<Time>,
+) {
+    if !stress_test.report.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let Some(frame_time) = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|diagnostic| diagnostic.average())
+    else {
+        return;
+    };
+
+    info!(
+        "Stress test: {} units, {frame_time:.2} ms per frame",
+        stress_test.units
+    );
+}
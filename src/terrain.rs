@@ -1,10 +1,21 @@
 use bevy::prelude::*;
-use bevy_rapier3d::prelude::{Collider, RapierPickable};
 
 use crate::{
-    config::terrain::TerrainConfig,
+    config::{economy::EconomyConfig, settings::Settings},
+    economy::{ResourceNode, node_near},
     game_states::GameState,
-    units::{MoveTo, Selected, UnitSelector, utils::remove_selection},
+    input::Action,
+    match_settings::MatchSettings,
+    orders::{
+        CommandTarget, LocalCommands, OrderGiven, OrderKind, OrderTargeting, PlayerCommand, UnitId,
+        complete_order_targeting,
+    },
+    picking::PickShape,
+    units::{
+        Selected, UnitSelector,
+        hero::{AbilityTargeting, complete_targeting},
+        utils::remove_selection,
+    },
 };
 
 pub struct TerrainPlugin;
@@ -24,22 +35,20 @@ fn setup(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    terrain_config_res: Res<TerrainConfig>,
+    match_settings: Res<MatchSettings>,
 ) {
-    println!("Spawing terrain");
+    let half_size = match_settings.map.half_size;
+
+    info!("Spawning terrain for {}", match_settings.map.name);
     commands
         .spawn((
             Name::new("Terrain"),
             Terrain,
             StateScoped(GameState::Playing),
-            Mesh3d(meshes.add(Plane3d::new(
-                Vec3::Y,
-                Vec2::new(terrain_config_res.x, terrain_config_res.y),
-            ))),
+            Mesh3d(meshes.add(Plane3d::new(Vec3::Y, half_size))),
             MeshMaterial3d(materials.add(Color::srgb_u8(111, 78, 55))),
             Transform::from_translation(Vec3::ZERO),
-            Collider::cuboid(terrain_config_res.x, 0.01, terrain_config_res.y),
-            RapierPickable,
+            PickShape::Ground { half_size },
         ))
         .observe(on_click);
 }
@@ -49,18 +58,59 @@ fn on_click(
     mut commands: Commands,
     selected_units: Query<Entity, (With<Selected>, Without<UnitSelector>)>,
     unit_selectors_selected: Query<Entity, (With<UnitSelector>, With<Selected>)>,
+    unit_ids: Query<&UnitId>,
+    targeting: Option<Res<AbilityTargeting>>,
+    order_targeting: Option<Res<OrderTargeting>>,
+    nodes_query: Query<&Transform, With<ResourceNode>>,
+    mut local_commands: ResMut<LocalCommands>,
+    settings: Res<Settings>,
+    economy_config: Res<EconomyConfig>,
 ) {
     let hit = click.hit.position.unwrap();
 
-    match click.button {
-        PointerButton::Primary => {
+    match settings.input.pointer_action(click.button) {
+        Some(Action::Select) => {
+            if complete_targeting(
+                &mut commands,
+                targeting,
+                &mut local_commands,
+                CommandTarget::Point(hit.xz()),
+            ) || complete_order_targeting(
+                &mut commands,
+                order_targeting,
+                &mut local_commands,
+                unit_ids.iter_many(&selected_units).copied().collect(),
+                CommandTarget::Point(hit.xz()),
+            ) {
+                return;
+            }
+
             remove_selection(&mut commands, selected_units, unit_selectors_selected);
         }
-        PointerButton::Secondary => {
-            for unit in selected_units {
-                commands.entity(unit).insert(MoveTo { target: hit.xz() });
+        Some(Action::Command) => {
+            let units: Vec<UnitId> = unit_ids.iter_many(&selected_units).copied().collect();
+            if !units.is_empty() {
+                commands.trigger(OrderGiven {
+                    kind: OrderKind::Move,
+                    target: CommandTarget::Point(hit.xz()),
+                });
+                // Mines are not pickable, a click close enough to one sends the units to work it
+                let node = node_near(
+                    nodes_query
+                        .iter()
+                        .map(|transform| transform.translation.xz()),
+                    hit.xz(),
+                    economy_config.gather_reach,
+                );
+                local_commands.0.push(match node {
+                    Some(node) => PlayerCommand::Gather { units, node },
+                    None => PlayerCommand::Move {
+                        units,
+                        target: hit.xz(),
+                    },
+                });
             }
         }
-        PointerButton::Middle => (),
+        _ => (),
     }
 }
@@ -0,0 +1,423 @@
+use std::{collections::HashMap, time::Duration};
+
+use bevy::{gltf::Gltf, prelude::*, scene::SceneInstanceReady};
+
+use crate::{
+    config::{models::ModelsConfig, weather::WeatherConfig},
+    economy::UnitKind,
+    game_states::GameState,
+    players::{Owner, Player},
+    simulation::InterpolatedTranslation,
+    units::{
+        Movement, SOLDIER_BODY, Unit, UnitBody,
+        combat::Attack,
+        health::UnitDied,
+        hero::{HERO_BODY, Hero},
+        rendering::{UnitAssets, UnitLod},
+    },
+    weather::WeatherState,
+};
+
+/// Meshes of a model using this material are drawn in the colour of the unit's owner.
+const TEAM_MATERIAL: &str = "team";
+/// How fast models turn to face where they go, the larger the snappier.
+const TURN_RATE: f32 = 10.;
+
+/// Draws units with animated glTF models instead of capsules, once the model of their archetype
+/// has loaded. Only added with a window, the simulation never looks at models.
+pub struct UnitAnimationPlugin;
+
+impl Plugin for UnitAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UnitModels>()
+            .add_observer(setup_model_instance)
+            .add_systems(Startup, load_models)
+            .add_systems(Update, prepare_models)
+            .add_systems(
+                Update,
+                (attach_models, spawn_corpses, animate_models, remove_corpses)
+                    .chain()
+                    .after(prepare_models)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnimationState {
+    Idle,
+    Walk,
+    Attack,
+    Die,
+}
+
+/// Everything needed to spawn and animate the model of an archetype.
+struct UnitModel {
+    scene: Handle<Scene>,
+    graph: Handle<AnimationGraph>,
+    animations: HashMap<AnimationState, AnimationNodeIndex>,
+    team_material: Option<Handle<StandardMaterial>>,
+}
+
+#[derive(Resource, Default)]
+struct UnitModels {
+    loading: HashMap<UnitKind, Handle<Gltf>>,
+    ready: HashMap<UnitKind, UnitModel>,
+}
+
+/// Root of the scene drawing a unit, a child of the unit itself or of nothing once it died.
+#[derive(Component)]
+pub struct ModelRoot {
+    pub kind: UnitKind,
+    pub color: Color,
+    /// The entity playing the animations, found once the scene is spawned
+    player: Option<Entity>,
+    state: Option<AnimationState>,
+}
+
+impl ModelRoot {
+    fn new(kind: UnitKind, color: Color) -> Self {
+        Self {
+            kind,
+            color,
+            player: None,
+            state: None,
+        }
+    }
+
+    /// The animation being played, or about to be once the scene is ready.
+    pub fn state(&self) -> Option<AnimationState> {
+        self.state
+    }
+}
+
+/// Set on units drawn with a model rather than a capsule.
+#[derive(Component)]
+pub struct HasModel(pub Entity);
+
+/// A fallen unit playing its death animation, removed when the timer finishes.
+#[derive(Component)]
+struct Corpse(Timer);
+
+fn body(kind: UnitKind) -> &'static UnitBody {
+    match kind {
+        UnitKind::Soldier => &SOLDIER_BODY,
+        UnitKind::Hero => &HERO_BODY,
+    }
+}
+
+fn load_models(
+    mut models: ResMut<UnitModels>,
+    asset_server: Res<AssetServer>,
+    models_config: Res<ModelsConfig>,
+) {
+    for kind in [UnitKind::Soldier, UnitKind::Hero] {
+        let handle = asset_server.load(models_config.model(kind).path.clone());
+        models.loading.insert(kind, handle);
+    }
+}
+
+// Not part of the loading state, so that a missing model leaves units as capsules instead of
+// stopping the game from starting.
+fn prepare_models(
+    mut models: ResMut<UnitModels>,
+    asset_server: Res<AssetServer>,
+    gltfs: Res<Assets<Gltf>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    models_config: Res<ModelsConfig>,
+) {
+    if models.loading.is_empty() {
+        return;
+    }
+
+    let done: Vec<UnitKind> = models
+        .loading
+        .iter()
+        .filter(|(_, handle)| {
+            gltfs.contains(*handle)
+                || asset_server
+                    .get_load_state(*handle)
+                    .is_some_and(|state| state.is_failed())
+        })
+        .map(|(kind, _)| *kind)
+        .collect();
+
+    for kind in done {
+        let handle = models.loading.remove(&kind).unwrap();
+        let config = models_config.model(kind);
+        let Some(gltf) = gltfs.get(&handle) else {
+            warn!(
+                "Could not load {}, drawing {kind:?} units as capsules",
+                config.path
+            );
+            continue;
+        };
+        let Some(scene) = gltf
+            .default_scene
+            .clone()
+            .or_else(|| gltf.scenes.first().cloned())
+        else {
+            warn!("{} has no scene", config.path);
+            continue;
+        };
+
+        let mut graph = AnimationGraph::new();
+        let mut animations = HashMap::new();
+        let names = &config.animations;
+        for (state, name) in [
+            (AnimationState::Idle, &names.idle),
+            (AnimationState::Walk, &names.walk),
+            (AnimationState::Attack, &names.attack),
+            (AnimationState::Die, &names.die),
+        ] {
+            match gltf.named_animations.get(name.as_str()) {
+                Some(clip) => {
+                    let node = graph.add_clip(clip.clone(), 1., graph.root);
+                    animations.insert(state, node);
+                }
+                None => warn!("{} has no animation named {name}", config.path),
+            }
+        }
+
+        models.ready.insert(
+            kind,
+            UnitModel {
+                scene,
+                graph: graphs.add(graph),
+                animations,
+                team_material: gltf.named_materials.get(TEAM_MATERIAL).cloned(),
+            },
+        );
+    }
+}
+
+fn attach_models(
+    mut commands: Commands,
+    models: Res<UnitModels>,
+    units_query: Query<(Entity, &Owner, Has<Hero>), (With<UnitLod>, Without<HasModel>)>,
+    players_query: Query<&Player>,
+) {
+    for (entity, owner, is_hero) in units_query.iter() {
+        let kind = if is_hero {
+            UnitKind::Hero
+        } else {
+            UnitKind::Soldier
+        };
+        let Some(model) = models.ready.get(&kind) else {
+            continue;
+        };
+        let Some(player) = players_query.iter().find(|player| player.id == owner.0) else {
+            continue;
+        };
+
+        // Models stand on the ground while units are centred on their capsule
+        let root = commands
+            .spawn((
+                Name::new("Model"),
+                ModelRoot::new(kind, player.color.color()),
+                SceneRoot(model.scene.clone()),
+                Transform::from_xyz(0., -body(kind).half_length, 0.),
+                ChildOf(entity),
+            ))
+            .id();
+
+        // The pick shape stays, so the unit is clicked the same with or without its model
+        commands.entity(entity).insert(HasModel(root)).remove::<(
+            Mesh3d,
+            MeshMaterial3d<StandardMaterial>,
+            UnitLod,
+        )>();
+    }
+}
+
+fn setup_model_instance(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    mut roots_query: Query<&mut ModelRoot>,
+    children_query: Query<&Children>,
+    players_query: Query<(), With<AnimationPlayer>>,
+    mut materials_query: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    models: Res<UnitModels>,
+    mut assets: UnitAssets,
+) {
+    let entity = trigger.target();
+    let Ok(mut root) = roots_query.get_mut(entity) else {
+        return;
+    };
+    let Some(model) = models.ready.get(&root.kind) else {
+        return;
+    };
+
+    if let Some(team_material) = &model.team_material {
+        let material = assets.material(root.color);
+        let mut materials = materials_query.iter_many_mut(children_query.iter_descendants(entity));
+        while let Some(mut mesh_material) = materials.fetch_next() {
+            if mesh_material.0 == *team_material {
+                mesh_material.0 = material.clone();
+            }
+        }
+    }
+
+    let Some(player) = children_query
+        .iter_descendants(entity)
+        .find(|descendant| players_query.contains(*descendant))
+    else {
+        return;
+    };
+    commands.entity(player).insert((
+        AnimationGraphHandle(model.graph.clone()),
+        AnimationTransitions::new(),
+    ));
+    root.player = Some(player);
+}
+
+fn spawn_corpses(
+    mut commands: Commands,
+    mut died_events: EventReader<UnitDied>,
+    models: Res<UnitModels>,
+    players_query: Query<&Player>,
+    models_config: Res<ModelsConfig>,
+) {
+    for died in died_events.read() {
+        let Some(model) = models.ready.get(&died.kind) else {
+            continue;
+        };
+        let color = died
+            .owner
+            .and_then(|owner| players_query.iter().find(|player| player.id == owner))
+            .map_or(Color::WHITE, |player| player.color.color());
+
+        commands.spawn((
+            Name::new("Corpse"),
+            StateScoped(GameState::Playing),
+            ModelRoot::new(died.kind, color),
+            Corpse(Timer::from_seconds(
+                models_config.corpse_seconds,
+                TimerMode::Once,
+            )),
+            SceneRoot(model.scene.clone()),
+            Transform::from_xyz(died.position.x, 0., died.position.z),
+        ));
+    }
+}
+
+/// What a unit is doing, as far as its animation is concerned.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UnitActivity {
+    /// Set for corpses, whose unit is gone
+    pub dead: bool,
+    /// Ground covered during the last tick
+    pub motion: Vec2,
+    /// Offset from the unit to the target of its attack
+    pub target: Option<Vec2>,
+    /// Speed of the unit, slowed down by the weather
+    pub speed: f32,
+}
+
+/// The animation matching `activity` and the speed to play it at. `walk_speed` is the speed at
+/// which the walk animation of the model matches the ground.
+pub fn choose_animation(activity: &UnitActivity, walk_speed: f32) -> (AnimationState, f32) {
+    if activity.dead {
+        (AnimationState::Die, 1.)
+    } else if activity.motion.length_squared() > f32::EPSILON {
+        // Moving wins over attacking, which covers units chasing their target
+        (AnimationState::Walk, activity.speed / walk_speed)
+    } else if activity.target.is_some() {
+        (AnimationState::Attack, 1.)
+    } else {
+        (AnimationState::Idle, 1.)
+    }
+}
+
+/// Picks the animation of every model from what its unit is doing, and turns it towards where it
+/// is going or what it is attacking.
+fn animate_models(
+    mut roots_query: Query<(
+        &mut ModelRoot,
+        &mut Transform,
+        Option<&ChildOf>,
+        Has<Corpse>,
+    )>,
+    units_query: Query<(&InterpolatedTranslation, &Movement, Option<&Attack>), With<Unit>>,
+    targets_query: Query<&GlobalTransform, With<Unit>>,
+    mut players_query: Query<(&mut AnimationPlayer, &mut AnimationTransitions)>,
+    models: Res<UnitModels>,
+    models_config: Res<ModelsConfig>,
+    weather_state: Res<WeatherState>,
+    weather_config: Res<WeatherConfig>,
+    time: Res<Time>,
+) {
+    let speed_multiplier = weather_config.get(weather_state.current).speed_multiplier;
+
+    for (mut root, mut transform, child_of, is_corpse) in roots_query.iter_mut() {
+        let Some(model) = models.ready.get(&root.kind) else {
+            continue;
+        };
+
+        let mut activity = UnitActivity {
+            dead: is_corpse,
+            ..default()
+        };
+        if let Some((interpolated, movement, attack)) =
+            child_of.and_then(|child_of| units_query.get(child_of.parent()).ok())
+        {
+            activity.motion = (interpolated.current - interpolated.previous).xz();
+            activity.target = attack
+                .and_then(|attack| targets_query.get(attack.target).ok())
+                .map(|target| (target.translation() - interpolated.current).xz());
+            activity.speed = movement.speed * speed_multiplier;
+        }
+
+        let (state, speed) = choose_animation(&activity, models_config.model(root.kind).walk_speed);
+        let facing = match state {
+            AnimationState::Walk => Some(activity.motion),
+            AnimationState::Attack => activity.target,
+            _ => None,
+        };
+
+        if let Some(direction) = facing.filter(|direction| *direction != Vec2::ZERO) {
+            // Models face +Z when not rotated
+            let wanted = Quat::from_rotation_y(direction.x.atan2(direction.y));
+            let blend = (TURN_RATE * time.delta_secs()).min(1.);
+            transform.rotation = transform.rotation.slerp(wanted, blend);
+        }
+
+        let Some((mut player, mut transitions)) = root
+            .player
+            .and_then(|player| players_query.get_mut(player).ok())
+        else {
+            continue;
+        };
+        let Some(&node) = model
+            .animations
+            .get(&state)
+            .or_else(|| model.animations.get(&AnimationState::Idle))
+        else {
+            continue;
+        };
+
+        if root.state != Some(state) {
+            let blend = Duration::from_secs_f32(models_config.blend_seconds);
+            let animation = transitions.play(&mut player, node, blend);
+            if state != AnimationState::Die {
+                animation.repeat();
+            }
+            root.state = Some(state);
+        }
+        if let Some(animation) = player.animation_mut(node) {
+            animation.set_speed(speed);
+        }
+    }
+}
+
+fn remove_corpses(
+    mut commands: Commands,
+    mut corpses_query: Query<(Entity, &mut Corpse)>,
+    time: Res<Time>,
+) {
+    for (entity, mut corpse) in corpses_query.iter_mut() {
+        if corpse.0.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
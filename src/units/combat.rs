@@ -0,0 +1,108 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+
+use crate::{
+    simulation::SimulationSet,
+    units::{MoveTo, health::Damage},
+};
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Weapon>()
+            .register_type::<Attack>()
+            .add_systems(
+                FixedUpdate,
+                (tick_weapons, attack)
+                    .chain()
+                    .in_set(SimulationSet::Attacks),
+            );
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Weapon {
+    pub damage: f32,
+    pub range: f32,
+    pub cooldown: Timer,
+}
+
+impl Weapon {
+    pub fn new(damage: f32, range: f32, cooldown_secs: f32) -> Self {
+        let mut cooldown = Timer::from_seconds(cooldown_secs, TimerMode::Once);
+        // Like abilities, weapons can fire as soon as the unit is spawned
+        cooldown.tick(cooldown.duration());
+
+        Self {
+            damage,
+            range,
+            cooldown,
+        }
+    }
+}
+
+/// Order to chase `target` and hit it with the unit's [`Weapon`] until one of them dies.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component, MapEntities)]
+pub struct Attack {
+    #[entities]
+    pub target: Entity,
+    /// Set for units that must not leave their position to reach the target
+    pub hold: bool,
+}
+
+// Entities are numbered differently once loaded from a save
+impl MapEntities for Attack {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.target = entity_mapper.get_mapped(self.target);
+    }
+}
+
+fn tick_weapons(mut weapons_query: Query<&mut Weapon>, time: Res<Time>) {
+    for mut weapon in weapons_query.iter_mut() {
+        weapon.cooldown.tick(time.delta());
+    }
+}
+
+fn attack(
+    mut commands: Commands,
+    mut attackers_query: Query<(Entity, &mut Weapon, &Attack, &Transform, Has<MoveTo>)>,
+    targets_query: Query<&Transform>,
+    mut damage_events: EventWriter<Damage>,
+) {
+    for (entity, mut weapon, attack, transform, moving) in attackers_query.iter_mut() {
+        let Ok(target_transform) = targets_query.get(attack.target) else {
+            // The target died or was despawned
+            commands.entity(entity).remove::<(Attack, MoveTo)>();
+            continue;
+        };
+
+        let position = transform.translation.xz();
+        let target = target_transform.translation.xz();
+
+        if position.distance_squared(target) > weapon.range.powi(2) {
+            if attack.hold {
+                commands.entity(entity).remove::<Attack>();
+            } else {
+                commands.entity(entity).insert(MoveTo { target });
+            }
+            continue;
+        }
+
+        if moving {
+            commands.entity(entity).remove::<MoveTo>();
+        }
+        if weapon.cooldown.finished() {
+            damage_events.write(Damage {
+                target: attack.target,
+                source: Some(entity),
+                amount: weapon.damage,
+            });
+            weapon.cooldown.reset();
+        }
+    }
+}
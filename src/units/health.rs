@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::{
+    economy::UnitKind,
+    orders::UnitId,
+    players::{Owner, PlayerId},
+    simulation::SimulationSet,
+    units::{Unit, hero::Hero},
+};
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Health>()
+            .add_event::<Damage>()
+            .add_event::<UnitDied>()
+            .add_systems(FixedUpdate, apply_damage.in_set(SimulationSet::Damage));
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn heal(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+}
+
+/// Request to remove `amount` of health from `target`.
+#[derive(Event, Debug)]
+pub struct Damage {
+    pub target: Entity,
+    /// The entity that dealt the damage, used to credit kills.
+    pub source: Option<Entity>,
+    pub amount: f32,
+}
+
+/// Sent right before a unit whose health dropped to zero is despawned. Buildings are despawned
+/// without it.
+#[derive(Event, Debug)]
+pub struct UnitDied {
+    pub entity: Entity,
+    /// Read here as the unit is gone by the time the event is handled
+    pub owner: Option<PlayerId>,
+    pub killer: Option<Entity>,
+    /// Where the unit fell, for the death animation
+    pub position: Vec3,
+    pub kind: UnitKind,
+}
+
+fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<Damage>,
+    mut died_events: EventWriter<UnitDied>,
+    mut health_query: Query<(
+        &mut Health,
+        Option<&Owner>,
+        &Transform,
+        Has<Unit>,
+        Has<Hero>,
+    )>,
+    ids_query: Query<&UnitId>,
+) {
+    // Events come in the order units are stored in, which differs between machines and decides
+    // who gets the kill, so they are played by source then target
+    let id = |entity: Entity| ids_query.get(entity).ok().copied();
+    let mut damages: Vec<&Damage> = damage_events.read().collect();
+    damages.sort_by_key(|damage| (damage.source.and_then(id), id(damage.target)));
+
+    for damage in damages {
+        let Ok((mut health, owner, transform, is_unit, is_hero)) =
+            health_query.get_mut(damage.target)
+        else {
+            continue;
+        };
+        // Several damage events can hit the same unit within a frame, only the first one to bring
+        // it down gets the kill.
+        if health.current <= 0. {
+            continue;
+        }
+
+        health.current = (health.current - damage.amount).max(0.);
+
+        if health.current <= 0. {
+            if is_unit {
+                died_events.write(UnitDied {
+                    entity: damage.target,
+                    owner: owner.map(|owner| owner.0),
+                    killer: damage.source,
+                    position: transform.translation,
+                    kind: if is_hero {
+                        UnitKind::Hero
+                    } else {
+                        UnitKind::Soldier
+                    },
+                });
+            }
+            commands.entity(damage.target).despawn();
+        }
+    }
+}
@@ -0,0 +1,537 @@
+use bevy::prelude::*;
+
+use crate::{
+    config::hero::HeroConfig,
+    economy::UnitKind,
+    game_states::GameState,
+    input::Action,
+    match_settings::MatchSettings,
+    orders::{
+        CommandTarget, LocalCommands, OrderGiven, OrderKind, OrderTargeting, PlayerCommand, UnitId,
+    },
+    players::{Owner, Player, spawn_players},
+    save::LoadedSave,
+    simulation::SimulationSet,
+    units::{
+        Movement, Selected, Unit, UnitBody,
+        combat::Weapon,
+        health::{Damage, Health, UnitDied},
+        rendering::UnitAssets,
+    },
+};
+
+pub const INVENTORY_SLOTS: usize = 6;
+
+pub struct HeroPlugin;
+
+impl Plugin for HeroPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Hero>()
+            .register_type::<Mana>()
+            .register_type::<Inventory>()
+            .register_type::<Abilities>()
+            .register_type::<Item>()
+            .register_type::<Ability>()
+            .add_event::<CastAbility>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                setup
+                    .after(spawn_players)
+                    .run_if(not(resource_exists::<LoadedSave>)),
+            )
+            .add_systems(Update, ability_hotkeys.run_if(in_state(GameState::Playing)))
+            .add_systems(
+                FixedUpdate,
+                (
+                    (
+                        regenerate_mana,
+                        tick_cooldowns,
+                        trigger_passives,
+                        cast_abilities,
+                    )
+                        .chain()
+                        .in_set(SimulationSet::Abilities),
+                    gain_experience.in_set(SimulationSet::Casualties),
+                ),
+            );
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Unit, Mana, Inventory, Abilities)]
+pub struct Hero {
+    pub level: u32,
+    pub experience: u32,
+}
+
+impl Default for Hero {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            experience: 0,
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Mana {
+    pub current: f32,
+    pub max: f32,
+    /// Mana restored every second
+    pub regeneration: f32,
+}
+
+#[derive(Reflect, Debug, Clone)]
+pub struct Item {
+    pub name: String,
+}
+
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Inventory {
+    pub slots: [Option<Item>; INVENTORY_SLOTS],
+}
+
+impl Inventory {
+    /// Puts the item in the first free slot, handing it back if the inventory is full.
+    pub fn insert(&mut self, item: Item) -> Result<usize, Item> {
+        match self.slots.iter().position(Option::is_none) {
+            Some(index) => {
+                self.slots[index] = Some(item);
+                Ok(index)
+            }
+            None => Err(item),
+        }
+    }
+}
+
+/// What an ability needs to be aimed at before it can be cast.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbilityTarget {
+    Point,
+    Unit,
+    SelfCast,
+    /// Never cast by the player, it triggers on the caster every time its cooldown is over.
+    Passive,
+}
+
+#[derive(Reflect, Debug, Clone, Copy)]
+pub enum AbilityEffect {
+    Damage(f32),
+    Heal(f32),
+    Teleport,
+}
+
+#[derive(Reflect, Debug, Clone)]
+pub struct Ability {
+    pub name: String,
+    pub target: AbilityTarget,
+    pub effect: AbilityEffect,
+    pub mana_cost: f32,
+    pub cooldown: Timer,
+    /// How far from the caster the target may be, abilities cast on the caster don't need one
+    pub range: Option<f32>,
+    /// `None` for passives, which the player never casts
+    pub hotkey: Option<Action>,
+}
+
+impl Ability {
+    pub fn new(
+        name: impl Into<String>,
+        target: AbilityTarget,
+        effect: AbilityEffect,
+        mana_cost: f32,
+        cooldown_secs: f32,
+        hotkey: Option<Action>,
+    ) -> Self {
+        let mut cooldown = Timer::from_seconds(cooldown_secs, TimerMode::Once);
+        // Abilities are ready to be used as soon as they are learned
+        cooldown.tick(cooldown.duration());
+
+        Self {
+            name: name.into(),
+            target,
+            effect,
+            mana_cost,
+            cooldown,
+            range: None,
+            hotkey,
+        }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.cooldown.finished()
+    }
+}
+
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Abilities(pub Vec<Ability>);
+
+/// The target an ability has been cast on.
+#[derive(Debug, Clone, Copy)]
+pub enum CastTarget {
+    Point(Vec2),
+    Unit(Entity),
+    Caster,
+}
+
+/// Request to cast the ability at `ability` index of the caster's [`Abilities`].
+#[derive(Event, Debug)]
+pub struct CastAbility {
+    pub caster: Entity,
+    pub ability: usize,
+    pub target: CastTarget,
+}
+
+/// Present while a point or unit targeted ability is waiting for the player to click its target.
+#[derive(Resource, Debug)]
+pub struct AbilityTargeting {
+    pub caster: UnitId,
+    pub ability: usize,
+    /// What the click must land on
+    pub target: AbilityTarget,
+}
+
+/// Turns a click into a [`PlayerCommand::CastAbility`] if an ability is waiting for a target.
+/// Returns `false` when nothing was waiting, so that the click can be handled as usual. A click on
+/// the wrong kind of target is ignored and the ability keeps waiting.
+pub fn complete_targeting(
+    commands: &mut Commands,
+    targeting: Option<Res<AbilityTargeting>>,
+    local_commands: &mut LocalCommands,
+    target: CommandTarget,
+) -> bool {
+    let Some(targeting) = targeting else {
+        return false;
+    };
+
+    match (targeting.target, target) {
+        (AbilityTarget::Point, CommandTarget::Point(_))
+        | (AbilityTarget::Unit, CommandTarget::Unit(_)) => (),
+        (expected, target) => {
+            info!("Cannot cast a {expected:?} targeted ability on {target:?}");
+            return true;
+        }
+    }
+
+    local_commands.0.push(PlayerCommand::CastAbility {
+        caster: targeting.caster,
+        ability: targeting.ability,
+        target,
+    });
+    commands.trigger(OrderGiven {
+        kind: OrderKind::Ability,
+        target,
+    });
+    commands.remove_resource::<AbilityTargeting>();
+
+    true
+}
+
+/// Casts a self cast ability straight away, the others wait for the player to click their target.
+/// Shared by the ability hotkeys and the command card.
+pub fn use_ability(
+    commands: &mut Commands,
+    local_commands: &mut LocalCommands,
+    caster: UnitId,
+    index: usize,
+    ability: &Ability,
+    mana: &Mana,
+) {
+    if !ability.is_ready() || mana.current < ability.mana_cost {
+        info!("{} is not ready", ability.name);
+        return;
+    }
+
+    match ability.target {
+        AbilityTarget::SelfCast => {
+            local_commands.0.push(PlayerCommand::CastAbility {
+                caster,
+                ability: index,
+                target: CommandTarget::Caster,
+            });
+        }
+        AbilityTarget::Point | AbilityTarget::Unit => {
+            commands.remove_resource::<OrderTargeting>();
+            commands.insert_resource(AbilityTargeting {
+                caster,
+                ability: index,
+                target: ability.target,
+            });
+        }
+        AbilityTarget::Passive => (),
+    }
+}
+
+pub(crate) const HERO_BODY: UnitBody = UnitBody {
+    kind: UnitKind::Hero,
+    radius: 0.12,
+    half_length: 0.35,
+};
+
+/// Spawns a level 1 hero for `player` standing at `position`.
+pub fn spawn_hero(
+    commands: &mut Commands,
+    assets: &mut UnitAssets,
+    player: &Player,
+    position: Vec2,
+) -> Entity {
+    let mut hero = commands.spawn((
+        Name::new("hero"),
+        Hero::default(),
+        Owner(player.id),
+        Transform::from_translation(Vec3::new(position.x, HERO_BODY.half_length, position.y)),
+        Movement { speed: 1.2 },
+        Health::new(200.),
+        Weapon::new(20., 0.6, 1.),
+        Mana {
+            current: 100.,
+            max: 100.,
+            regeneration: 1.,
+        },
+        Abilities(vec![
+            Ability::new(
+                "Strike",
+                AbilityTarget::Unit,
+                AbilityEffect::Damage(50.),
+                20.,
+                5.,
+                Some(Action::Ability1),
+            )
+            .with_range(3.),
+            Ability::new(
+                "Blink",
+                AbilityTarget::Point,
+                AbilityEffect::Teleport,
+                30.,
+                10.,
+                Some(Action::Ability2),
+            )
+            .with_range(6.),
+            Ability::new(
+                "Second Wind",
+                AbilityTarget::SelfCast,
+                AbilityEffect::Heal(60.),
+                40.,
+                20.,
+                Some(Action::Ability3),
+            ),
+            Ability::new(
+                "Regeneration",
+                AbilityTarget::Passive,
+                AbilityEffect::Heal(2.),
+                0.,
+                1.,
+                None,
+            ),
+        ]),
+    ));
+    HERO_BODY.attach(&mut hero, assets, player.color.color(), false);
+
+    hero.id()
+}
+
+fn setup(
+    mut commands: Commands,
+    mut assets: UnitAssets,
+    players_query: Query<&Player>,
+    match_settings: Res<MatchSettings>,
+) {
+    for player in players_query.iter() {
+        let start = match_settings.map.start_positions[player.id.0 as usize];
+        spawn_hero(
+            &mut commands,
+            &mut assets,
+            player,
+            start + Vec2::new(0., 0.5),
+        );
+    }
+}
+
+fn gain_experience(
+    mut died_events: EventReader<UnitDied>,
+    mut heroes: Query<(&mut Hero, &mut Health, &mut Mana)>,
+    hero_config: Res<HeroConfig>,
+) {
+    for died in died_events.read() {
+        let Some(killer) = died.killer else {
+            continue;
+        };
+        let Ok((mut hero, mut health, mut mana)) = heroes.get_mut(killer) else {
+            continue;
+        };
+        if hero.level >= hero_config.max_level {
+            continue;
+        }
+
+        hero.experience += hero_config.experience_per_kill;
+
+        while hero.level < hero_config.max_level
+            && hero.experience >= hero_config.experience_to_level_up(hero.level)
+        {
+            hero.experience -= hero_config.experience_to_level_up(hero.level);
+            hero.level += 1;
+
+            health.max += hero_config.health_per_level;
+            health.current = health.max;
+            mana.max += hero_config.mana_per_level;
+            mana.current = mana.max;
+
+            info!("Hero {killer} reached level {}", hero.level);
+        }
+    }
+}
+
+fn regenerate_mana(mut mana_query: Query<&mut Mana>, time: Res<Time>) {
+    for mut mana in mana_query.iter_mut() {
+        mana.current = (mana.current + mana.regeneration * time.delta_secs()).min(mana.max);
+    }
+}
+
+fn tick_cooldowns(mut abilities_query: Query<&mut Abilities>, time: Res<Time>) {
+    for mut abilities in abilities_query.iter_mut() {
+        for ability in abilities.0.iter_mut() {
+            ability.cooldown.tick(time.delta());
+        }
+    }
+}
+
+fn ability_hotkeys(
+    mut commands: Commands,
+    actions: Res<ButtonInput<Action>>,
+    heroes: Query<(&UnitId, &Abilities, &Mana), (With<Hero>, With<Selected>)>,
+    mut local_commands: ResMut<LocalCommands>,
+) {
+    if actions.just_pressed(Action::CancelAbility) {
+        commands.remove_resource::<AbilityTargeting>();
+    }
+
+    // With several heroes selected, hotkeys go to the first one
+    let Some((caster, abilities, mana)) = heroes.iter().next() else {
+        return;
+    };
+
+    for (index, ability) in abilities.0.iter().enumerate() {
+        if !ability
+            .hotkey
+            .is_some_and(|hotkey| actions.just_pressed(hotkey))
+        {
+            continue;
+        }
+        use_ability(
+            &mut commands,
+            &mut local_commands,
+            *caster,
+            index,
+            ability,
+            mana,
+        );
+    }
+}
+
+fn trigger_passives(
+    abilities_query: Query<(Entity, &Abilities)>,
+    mut casts: EventWriter<CastAbility>,
+) {
+    for (caster, abilities) in abilities_query.iter() {
+        for (index, ability) in abilities.0.iter().enumerate() {
+            if ability.target == AbilityTarget::Passive && ability.is_ready() {
+                casts.write(CastAbility {
+                    caster,
+                    ability: index,
+                    target: CastTarget::Caster,
+                });
+            }
+        }
+    }
+}
+
+fn cast_abilities(
+    mut cast_events: EventReader<CastAbility>,
+    mut casters: Query<(&mut Abilities, &mut Mana)>,
+    mut health_query: Query<&mut Health>,
+    mut transforms: Query<&mut Transform>,
+    mut damage_events: EventWriter<Damage>,
+) {
+    for cast in cast_events.read() {
+        let Ok((mut abilities, mut mana)) = casters.get_mut(cast.caster) else {
+            continue;
+        };
+        let Some(ability) = abilities.0.get_mut(cast.ability) else {
+            continue;
+        };
+        // Checked again as the ability may have been used while waiting for a target
+        if !ability.is_ready() || mana.current < ability.mana_cost {
+            continue;
+        }
+        if let Some(range) = ability.range {
+            let caster_position = transforms
+                .get(cast.caster)
+                .map(|transform| transform.translation);
+            let target_position = match cast.target {
+                CastTarget::Point(point) => Ok(Vec3::new(point.x, 0., point.y)),
+                CastTarget::Unit(target) => transforms
+                    .get(target)
+                    .map(|transform| transform.translation),
+                CastTarget::Caster => caster_position,
+            };
+            let in_range = match (caster_position, target_position) {
+                (Ok(caster), Ok(target)) => {
+                    caster.xz().distance_squared(target.xz()) <= range.powi(2)
+                }
+                _ => false,
+            };
+            if !in_range {
+                info!("{} is out of range", ability.name);
+                continue;
+            }
+        }
+
+        let target = match (ability.effect, cast.target) {
+            (AbilityEffect::Damage(amount), CastTarget::Unit(target)) => {
+                damage_events.write(Damage {
+                    target,
+                    source: Some(cast.caster),
+                    amount,
+                });
+                target
+            }
+            (AbilityEffect::Heal(amount), CastTarget::Unit(_) | CastTarget::Caster) => {
+                let target = match cast.target {
+                    CastTarget::Unit(target) => target,
+                    _ => cast.caster,
+                };
+                if let Ok(mut health) = health_query.get_mut(target) {
+                    health.heal(amount);
+                }
+                target
+            }
+            (AbilityEffect::Teleport, CastTarget::Point(point)) => {
+                if let Ok(mut transform) = transforms.get_mut(cast.caster) {
+                    transform.translation.x = point.x;
+                    transform.translation.z = point.y;
+                }
+                cast.caster
+            }
+            (effect, target) => {
+                warn!("{} cannot apply {effect:?} on {target:?}", ability.name);
+                continue;
+            }
+        };
+
+        mana.current -= ability.mana_cost;
+        ability.cooldown.reset();
+
+        if ability.target != AbilityTarget::Passive {
+            info!("{} cast {} on {target}", cast.caster, ability.name);
+        }
+    }
+}
@@ -1,21 +1,67 @@
 use bevy::prelude::*;
-use bevy_rapier3d::prelude::{Collider, RapierPickable};
 
 use crate::{
+    config::{settings::Settings, weather::WeatherConfig},
+    economy::UnitKind,
     game_states::GameState,
-    units::{selection::SelectionPlugin, utils::remove_selection},
+    input::Action,
+    match_settings::MatchSettings,
+    orders::{
+        CommandTarget, LocalCommands, OrderGiven, OrderKind, OrderTargeting, PlayerCommand, UnitId,
+        complete_order_targeting,
+    },
+    picking::PickShape,
+    players::{LocalPlayer, Owner, Player, spawn_players},
+    save::LoadedSave,
+    simulation::SimulationSet,
+    units::{
+        combat::{CombatPlugin, Weapon},
+        health::{Health, HealthPlugin},
+        hero::{AbilityTargeting, HeroPlugin, complete_targeting},
+        rendering::{UnitAssetCache, UnitAssets, UnitLod},
+        selection::SelectionPlugin,
+        spatial::SpatialPlugin,
+        tactics::TacticsPlugin,
+        utils::{add_selection, remove_selection},
+    },
+    weather::WeatherState,
 };
 
+pub mod animation;
+pub mod combat;
+pub mod health;
+pub mod hero;
+pub mod rendering;
 pub mod selection;
+pub mod spatial;
+pub mod tactics;
 pub mod utils;
 
 pub struct UnitsPlugin;
 
 impl Plugin for UnitsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(SelectionPlugin)
-            .add_systems(OnEnter(GameState::Playing), setup)
-            .add_systems(Update, movement.run_if(in_state(GameState::Playing)));
+        app.register_type::<Unit>()
+            .register_type::<UnitSelector>()
+            .register_type::<Selected>()
+            .register_type::<Movement>()
+            .register_type::<MoveTo>()
+            .init_resource::<UnitAssetCache>()
+            .add_plugins((
+                SelectionPlugin,
+                HealthPlugin,
+                HeroPlugin,
+                CombatPlugin,
+                TacticsPlugin,
+                SpatialPlugin,
+            ))
+            .add_systems(
+                OnEnter(GameState::Playing),
+                setup
+                    .after(spawn_players)
+                    .run_if(not(resource_exists::<LoadedSave>)),
+            )
+            .add_systems(FixedUpdate, movement.in_set(SimulationSet::Movement));
     }
 }
 
@@ -39,113 +85,205 @@ pub struct MoveTo {
     pub target: Vec2,
 }
 
-#[derive(Component, Reflect, Debug)]
+#[derive(Component, Reflect, Debug, Default)]
 #[reflect(Component)]
-#[require(Transform, Collider)]
+#[require(Transform)]
 pub struct Unit;
 
-fn setup(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    let radius = 0.1_f32;
-    let half_length = 0.3_f32;
-
-    commands
-        .spawn((
-            Name::new("unit"),
-            Unit,
-            Mesh3d(meshes.add(Capsule3d::new(radius, half_length))),
-            Transform::from_translation(Vec3::new(0., half_length, 0.)),
-            MeshMaterial3d(materials.add(Color::srgb_u8(50, 50, 200))),
-            Collider::capsule_y(half_length / 2., radius),
-            RapierPickable,
-            children![(
-                Name::new("Selector"),
-                UnitSelector,
-                Mesh3d(meshes.add(Torus::new(0.15, 0.17))),
-                MeshMaterial3d(materials.add(Color::srgb_u8(0, 200, 0))),
-                Transform::from_xyz(0., -0.3, 0.),
-                Visibility::Hidden,
-            )],
-            Movement { speed: 1.0 },
-        ))
-        .observe(on_click);
+/// Shape of the capsule a unit is drawn with, coloured after its owner.
+pub(crate) struct UnitBody {
+    /// Units of the same kind share their meshes, see [`UnitAssets`]
+    pub kind: UnitKind,
+    pub radius: f32,
+    pub half_length: f32,
+}
 
-    commands
-        .spawn((
-            Name::new("unit"),
-            Unit,
-            Mesh3d(meshes.add(Capsule3d::new(radius, half_length))),
-            Transform::from_translation(Vec3::new(2., half_length, 2.)),
-            MeshMaterial3d(materials.add(Color::srgb_u8(50, 50, 200))),
-            Collider::capsule_y(half_length / 2., radius),
-            RapierPickable,
-            children![(
+pub(crate) const SOLDIER_BODY: UnitBody = UnitBody {
+    kind: UnitKind::Soldier,
+    radius: 0.1,
+    half_length: 0.3,
+};
+
+impl UnitBody {
+    /// Gives the unit its mesh and selector ring, and makes it clickable.
+    /// Everything added here is rebuilt rather than saved, see [`crate::save`].
+    pub(crate) fn attach(
+        &self,
+        unit: &mut EntityCommands,
+        assets: &mut UnitAssets,
+        color: Color,
+        selected: bool,
+    ) {
+        let meshes = assets.body(self);
+        let material = assets.material(color);
+        let selector_material = assets.selector_material();
+
+        unit.insert((
+            StateScoped(GameState::Playing),
+            Mesh3d(meshes.high.clone()),
+            MeshMaterial3d(material),
+            UnitLod {
+                high: meshes.high,
+                low: meshes.low,
+            },
+            PickShape::Unit {
+                radius: self.radius,
+                half_height: self.half_length / 2.,
+            },
+        ))
+        .with_children(|parent| {
+            let mut selector = parent.spawn((
                 Name::new("Selector"),
                 UnitSelector,
-                Mesh3d(meshes.add(Torus::new(0.15, 0.17))),
-                MeshMaterial3d(materials.add(Color::srgb_u8(0, 200, 0))),
-                Transform::from_xyz(0., -0.3, 0.),
+                Mesh3d(meshes.selector),
+                MeshMaterial3d(selector_material),
+                Transform::from_xyz(0., -self.half_length, 0.),
                 Visibility::Hidden,
-            )],
-            Movement { speed: 1.0 },
-        ))
+            ));
+            if selected {
+                selector.insert((Visibility::Inherited, Selected));
+            }
+        })
         .observe(on_click);
+    }
 }
 
-fn on_click(
+/// Spawns a basic soldier for `owner` standing at `position`.
+pub fn spawn_soldier(
+    commands: &mut Commands,
+    assets: &mut UnitAssets,
+    player: &Player,
+    position: Vec2,
+) -> Entity {
+    let mut unit = commands.spawn((
+        Name::new("unit"),
+        Unit,
+        Owner(player.id),
+        Transform::from_translation(Vec3::new(position.x, SOLDIER_BODY.half_length, position.y)),
+        Movement { speed: 1.0 },
+        Health::new(100.),
+        Weapon::new(10., 0.5, 1.),
+    ));
+    SOLDIER_BODY.attach(&mut unit, assets, player.color.color(), false);
+
+    unit.id()
+}
+
+fn setup(
+    mut commands: Commands,
+    mut assets: UnitAssets,
+    players_query: Query<&Player>,
+    match_settings: Res<MatchSettings>,
+) {
+    for player in players_query.iter() {
+        let start = match_settings.map.start_positions[player.id.0 as usize];
+
+        for offset in [Vec2::new(0.5, 0.), Vec2::new(-0.5, 0.)] {
+            spawn_soldier(&mut commands, &mut assets, player, start + offset);
+        }
+    }
+}
+
+pub(crate) fn on_click(
     click: Trigger<Pointer<Click>>,
-    world: &World,
     mut commands: Commands,
+    children_query: Query<&Children>,
+    unit_selectors: Query<(), With<UnitSelector>>,
     selected_units: Query<Entity, (With<Selected>, Without<UnitSelector>)>,
     unit_selectors_selected: Query<Entity, (With<UnitSelector>, With<Selected>)>,
+    targeting: Option<Res<AbilityTargeting>>,
+    order_targeting: Option<Res<OrderTargeting>>,
+    mut local_commands: ResMut<LocalCommands>,
+    settings: Res<Settings>,
+    owners: Query<&Owner>,
+    unit_ids: Query<&UnitId>,
+    local_player: Res<LocalPlayer>,
 ) {
-    match click.button {
-        PointerButton::Primary => {
-            // Remove for previously selected
-            remove_selection(&mut commands, selected_units, unit_selectors_selected);
-
-            // Add for just selected
-            commands.entity(click.target).insert(Selected);
-            if let Some(children) = world.get::<Children>(click.target) {
-                for child in children {
-                    if let Some(_) = world.get::<UnitSelector>(*child) {
-                        commands
-                            .entity(*child)
-                            .insert((Visibility::Inherited, Selected));
-                    }
+    // Units get their id on the first tick after they spawn, they cannot be commanded before
+    let Ok(target) = unit_ids.get(click.target) else {
+        return;
+    };
+
+    match settings.input.pointer_action(click.button) {
+        Some(Action::Select) => (),
+        Some(Action::Command) => {
+            // Commanding on another player's unit attacks it
+            if owners.get(click.target).ok() != Some(&Owner(local_player.0)) {
+                let units: Vec<UnitId> = unit_ids.iter_many(&selected_units).copied().collect();
+                // Nothing to send to the other players without units to order
+                if !units.is_empty() {
+                    commands.trigger(OrderGiven {
+                        kind: OrderKind::Attack,
+                        target: CommandTarget::Unit(*target),
+                    });
+                    local_commands.0.push(PlayerCommand::Attack {
+                        units,
+                        target: *target,
+                    });
                 }
             }
+            return;
         }
-        PointerButton::Secondary => todo!(),
-        PointerButton::Middle => todo!(),
-    };
-}
+        _ => return,
+    }
+
+    if complete_targeting(
+        &mut commands,
+        targeting,
+        &mut local_commands,
+        CommandTarget::Unit(*target),
+    ) || complete_order_targeting(
+        &mut commands,
+        order_targeting,
+        &mut local_commands,
+        unit_ids.iter_many(&selected_units).copied().collect(),
+        CommandTarget::Unit(*target),
+    ) {
+        return;
+    }
+
+    // Other players' units cannot be selected
+    if owners.get(click.target).ok() != Some(&Owner(local_player.0)) {
+        return;
+    }
+
+    // Remove for previously selected
+    remove_selection(&mut commands, selected_units, unit_selectors_selected);
 
-fn close_enough(v1: Vec2, v2: Vec2) -> bool {
-    // We use distance_squared as square roots are generally computationally heavy and in this case
-    // we do not need that amount of precision.
-    v1.distance_squared(v2) < f32::EPSILON
+    // Add for just selected
+    add_selection(
+        &mut commands,
+        click.target,
+        children_query.get(click.target).ok(),
+        &unit_selectors,
+    );
 }
 
 fn movement(
     mut commands: Commands,
     mut units_to_move_query: Query<(&mut Transform, &Movement, &MoveTo, Entity), With<Movement>>,
+    weather_state: Res<WeatherState>,
+    weather_config: Res<WeatherConfig>,
     time: Res<Time>,
 ) {
+    let speed_multiplier = weather_config.get(weather_state.current).speed_multiplier;
+
     for (mut transform, movement, move_to, entity) in units_to_move_query.iter_mut() {
         let origin = transform.translation.xz();
         let destination = move_to.target;
 
-        if close_enough(origin, destination) {
+        let delta = time.delta_secs() * movement.speed * speed_multiplier;
+        let offset = destination - origin;
+
+        // Stepping by `delta` would overshoot, so the unit is put right on its destination
+        if offset.length_squared() <= delta * delta {
+            transform.translation.x = destination.x;
+            transform.translation.z = destination.y;
             commands.entity(entity).remove::<MoveTo>();
+            continue;
         }
 
-        let delta = time.delta_secs() * movement.speed;
-        let direction = (destination - origin).normalize();
-
+        let direction = offset.normalize();
         transform.translation += delta * Vec3::new(direction.x, 0., direction.y);
     }
 }
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{camera::MainCamera, economy::UnitKind, game_states::GameState, units::UnitBody};
+
+/// Distance from the camera past which units are drawn with fewer triangles. Orthographic
+/// cameras count as further away the more they are zoomed out.
+const LOW_DETAIL_DISTANCE: f32 = 20.;
+
+/// Switches units to simpler meshes when they are far from the camera. Only added with a window,
+/// the meshes themselves are shared by [`UnitAssets`] in headless runs too.
+pub struct UnitRenderingPlugin;
+
+impl Plugin for UnitRenderingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_lod.run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct BodyMeshes {
+    pub high: Handle<Mesh>,
+    pub low: Handle<Mesh>,
+    pub selector: Handle<Mesh>,
+}
+
+/// Handles created so far by [`UnitAssets`], kept from one match to the next.
+#[derive(Resource, Default)]
+pub struct UnitAssetCache {
+    bodies: HashMap<UnitKind, BodyMeshes>,
+    /// Keyed by the colour in sRGB, as [`Color`] cannot be hashed
+    materials: HashMap<[u8; 4], Handle<StandardMaterial>>,
+    selector_material: Option<Handle<StandardMaterial>>,
+}
+
+/// Meshes and materials shared by every unit of the same kind and colour. Entities with the same
+/// mesh and material are drawn as one instanced batch, a handle per unit would cost a draw call
+/// each.
+#[derive(SystemParam)]
+pub struct UnitAssets<'w> {
+    cache: ResMut<'w, UnitAssetCache>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+}
+
+impl UnitAssets<'_> {
+    pub(crate) fn body(&mut self, body: &UnitBody) -> BodyMeshes {
+        let meshes = &mut self.meshes;
+        self.cache
+            .bodies
+            .entry(body.kind)
+            .or_insert_with(|| {
+                let capsule = Capsule3d::new(body.radius, body.half_length);
+                BodyMeshes {
+                    high: meshes.add(capsule),
+                    low: meshes.add(capsule.mesh().longitudes(8).latitudes(4).build()),
+                    selector: meshes.add(Torus::new(body.radius + 0.05, body.radius + 0.07)),
+                }
+            })
+            .clone()
+    }
+
+    pub(crate) fn material(&mut self, color: Color) -> Handle<StandardMaterial> {
+        let materials = &mut self.materials;
+        self.cache
+            .materials
+            .entry(color.to_srgba().to_u8_array())
+            .or_insert_with(|| materials.add(color))
+            .clone()
+    }
+
+    pub(crate) fn selector_material(&mut self) -> Handle<StandardMaterial> {
+        let materials = &mut self.materials;
+        self.cache
+            .selector_material
+            .get_or_insert_with(|| materials.add(Color::srgb_u8(0, 200, 0)))
+            .clone()
+    }
+}
+
+/// The meshes a unit switches between depending on its distance to the camera.
+#[derive(Component)]
+pub struct UnitLod {
+    pub high: Handle<Mesh>,
+    pub low: Handle<Mesh>,
+}
+
+fn update_lod(
+    camera: Single<(&GlobalTransform, &Projection), With<MainCamera>>,
+    mut units_query: Query<(&GlobalTransform, &UnitLod, &mut Mesh3d)>,
+) {
+    let (camera, projection) = camera.into_inner();
+    let zoom = match projection {
+        Projection::Orthographic(orthographic) => orthographic.scale,
+        _ => 1.,
+    };
+
+    for (transform, lod, mut mesh) in units_query.iter_mut() {
+        let distance = camera.translation().distance(transform.translation()) * zoom;
+        let wanted = if distance > LOW_DETAIL_DISTANCE {
+            &lod.low
+        } else {
+            &lod.high
+        };
+
+        // Compared first, so that the mesh is only cloned and marked changed on a switch
+        if mesh.0 != *wanted {
+            mesh.0 = wanted.clone();
+        }
+    }
+}
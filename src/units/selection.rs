@@ -1,6 +1,22 @@
-use bevy::prelude::*;
+use bevy::{input::common_conditions::input_just_released, prelude::*};
 
-use crate::game_states::GameState;
+use crate::{
+    camera::MainCamera,
+    game_states::GameState,
+    input::Action,
+    players::{LocalPlayer, Owner},
+    units::{
+        Selected, Unit, UnitSelector,
+        spatial::NearbyUnits,
+        utils::{add_selection, remove_selection},
+    },
+};
+
+/// Boxes smaller than this many pixels are clicks, left to the unit and terrain observers.
+const MIN_BOX_SIZE: f32 = 5.;
+/// Added around the ground under the box, as units stand above the ground and are seen at an
+/// angle.
+const GROUND_MARGIN: f32 = 1.;
 
 pub struct SelectionPlugin;
 
@@ -11,6 +27,9 @@ impl Plugin for SelectionPlugin {
             .add_systems(
                 Update,
                 (
+                    select_in_box.before(mouse_click).run_if(
+                        in_state(GameState::Playing).and(input_just_released(Action::Select)),
+                    ),
                     mouse_click.run_if(in_state(GameState::Playing)),
                     update_selection_box.run_if(
                         in_state(GameState::Playing).and(in_state(SelectionState::Selecting)),
@@ -53,8 +72,8 @@ fn create_selection_box(mut commands: Commands, window: Single<&Window>) {
             position_type: PositionType::Absolute,
             width: Val::Px(0.),
             height: Val::Px(0.),
-            left: left,
-            top: top,
+            left,
+            top,
             ..default()
         },
         BackgroundColor(color),
@@ -63,19 +82,77 @@ fn create_selection_box(mut commands: Commands, window: Single<&Window>) {
 
 fn mouse_click(
     mut commands: Commands,
-    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    actions: Res<ButtonInput<Action>>,
     mut next_state: ResMut<NextState<SelectionState>>,
     selection_box_query: Query<Entity, With<SelectionBox>>,
 ) {
-    if mouse_button_input.pressed(MouseButton::Left) {
+    if actions.pressed(Action::Select) {
         next_state.set(SelectionState::Selecting);
     }
-    if mouse_button_input.just_released(MouseButton::Left) {
+    if actions.just_released(Action::Select) {
         next_state.set(SelectionState::None);
         if let Ok(selection_box) = selection_box_query.single() {
             commands.entity(selection_box).despawn();
-        } else {
-            return;
+        }
+    }
+}
+
+/// Selects every unit of the local player whose position is drawn inside the box.
+fn select_in_box(
+    mut commands: Commands,
+    window: Single<&Window>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
+    selection_box: Single<&SelectionBox>,
+    units_query: Query<(Entity, &GlobalTransform, &Owner, Option<&Children>), With<Unit>>,
+    unit_selectors: Query<(), With<UnitSelector>>,
+    selected_units: Query<Entity, (With<Selected>, Without<UnitSelector>)>,
+    unit_selectors_selected: Query<Entity, (With<UnitSelector>, With<Selected>)>,
+    nearby_units: NearbyUnits,
+    local_player: Res<LocalPlayer>,
+) {
+    let Some(mouse_pos) = window.cursor_position() else {
+        return;
+    };
+    let area = Rect::from_corners(selection_box.origin, mouse_pos);
+    if area.width() < MIN_BOX_SIZE && area.height() < MIN_BOX_SIZE {
+        return;
+    }
+    let (camera, camera_transform) = camera_query.into_inner();
+
+    // Only units standing on the ground under the box are checked, all of them when part of the
+    // box is above the horizon
+    let corners = [
+        area.min,
+        area.max,
+        Vec2::new(area.min.x, area.max.y),
+        Vec2::new(area.max.x, area.min.y),
+    ]
+    .map(|corner| {
+        let ray = camera.viewport_to_world(camera_transform, corner).ok()?;
+        let distance = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Y))?;
+        Some(ray.get_point(distance).xz())
+    });
+    let candidates: Vec<Entity> = match corners {
+        [Some(a), Some(b), Some(c), Some(d)] => {
+            let ground = Rect::from_corners(a, b).union_point(c).union_point(d);
+            nearby_units
+                .within_rect(ground.inflate(GROUND_MARGIN))
+                .collect()
+        }
+        _ => units_query.iter().map(|(unit, ..)| unit).collect(),
+    };
+
+    remove_selection(&mut commands, selected_units, unit_selectors_selected);
+    for (unit, transform, owner, children) in units_query.iter_many(&candidates) {
+        if owner.0 != local_player.0 {
+            continue;
+        }
+        let Ok(position) = camera.world_to_viewport(camera_transform, transform.translation())
+        else {
+            continue;
+        };
+        if area.contains(position) {
+            add_selection(&mut commands, unit, children, &unit_selectors);
         }
     }
 }
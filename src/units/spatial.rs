@@ -0,0 +1,233 @@
+use std::{collections::HashMap, ops::Deref};
+
+use bevy::{
+    ecs::{entity::EntityHashMap, system::SystemParam},
+    prelude::*,
+};
+
+use crate::{
+    orders::UnitId,
+    simulation::{InterpolatedTranslation, SimulationSet},
+    units::Unit,
+};
+
+/// Width of a cell of the grid, around the range at which units usually look for each other.
+pub const DEFAULT_CELL_SIZE: f32 = 2.;
+
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex>()
+            .add_observer(forget_unit)
+            .add_systems(
+                FixedUpdate,
+                update_spatial_index.in_set(SimulationSet::Index),
+            );
+    }
+}
+
+/// Units bucketed in a uniform grid over the ground, so that finding the ones near a point only
+/// looks at a few cells instead of every unit. Systems read it through [`NearbyUnits`].
+#[derive(Resource, Debug)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    /// Kept sorted by [`UnitId`], so that they don't depend on the order entities were added in
+    cells: HashMap<IVec2, Vec<(UnitId, Entity)>>,
+    entries: EntityHashMap<Entry>,
+}
+
+/// Where an indexed entity is.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    id: UnitId,
+    cell: IVec2,
+    position: Vec2,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entries: EntityHashMap::default(),
+        }
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Where `entity` was when last indexed.
+    pub fn position(&self, entity: Entity) -> Option<Vec2> {
+        self.entries.get(&entity).map(|entry| entry.position)
+    }
+
+    /// Adds `entity` at `position`, or moves it there if it is already indexed. Only entities
+    /// leaving their cell are moved between buckets.
+    pub fn insert(&mut self, entity: Entity, id: UnitId, position: Vec2) {
+        let cell = self.cell(position);
+
+        match self.entries.insert(entity, Entry { id, cell, position }) {
+            Some(previous) if previous.cell == cell => return,
+            Some(previous) => self.remove_from_cell(previous.id, previous.cell),
+            None => (),
+        }
+        let bucket = self.cells.entry(cell).or_default();
+        let index = bucket.partition_point(|(other, _)| *other < id);
+        bucket.insert(index, (id, entity));
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(entry) = self.entries.remove(&entity) {
+            self.remove_from_cell(entry.id, entry.cell);
+        }
+    }
+
+    fn remove_from_cell(&mut self, id: UnitId, cell: IVec2) {
+        let Some(bucket) = self.cells.get_mut(&cell) else {
+            return;
+        };
+        if let Ok(index) = bucket.binary_search_by_key(&id, |(other, _)| *other) {
+            bucket.remove(index);
+        }
+        if bucket.is_empty() {
+            self.cells.remove(&cell);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    /// Entities in the cells overlapping `area`, cell by cell and by [`UnitId`] within a cell. The
+    /// order only depends on where the entities are, so every machine agrees on it.
+    fn candidates(&self, area: Rect) -> impl Iterator<Item = (Entity, Entry)> + '_ {
+        let min = self.cell(area.min);
+        let max = self.cell(area.max);
+        let span = (max - min + IVec2::ONE).as_i64vec2();
+
+        // Areas larger than the occupied part of the grid go through the occupied cells instead,
+        // sorted as the map iterates them in a different order on every machine
+        let cells: Vec<IVec2> = if span.x * span.y > self.cells.len() as i64 {
+            let mut cells: Vec<IVec2> = self
+                .cells
+                .keys()
+                .filter(|cell| cell.cmpge(min).all() && cell.cmple(max).all())
+                .copied()
+                .collect();
+            cells.sort_by_key(|cell| (cell.x, cell.y));
+            cells
+        } else {
+            (min.x..=max.x)
+                .flat_map(|x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+                .collect()
+        };
+
+        cells
+            .into_iter()
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .map(|(_, entity)| (*entity, self.entries[entity]))
+    }
+
+    /// Entities at most `radius` away from `center`.
+    pub fn within_radius(&self, center: Vec2, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        let radius_squared = radius * radius;
+
+        self.candidates(Rect::from_center_half_size(center, Vec2::splat(radius)))
+            .filter(move |(_, entry)| entry.position.distance_squared(center) <= radius_squared)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Entities inside `area`, edges included.
+    pub fn within_rect(&self, area: Rect) -> impl Iterator<Item = Entity> + '_ {
+        self.candidates(area)
+            .filter(move |(_, entry)| area.contains(entry.position))
+            .map(|(entity, _)| entity)
+    }
+
+    /// Up to `count` entities closest to `center`, nearest first. Equally distant entities are
+    /// ordered by [`UnitId`], as entities differ from one machine to the next.
+    pub fn nearest(&self, center: Vec2, count: usize) -> Vec<Entity> {
+        if count == 0 || self.is_empty() {
+            return Vec::new();
+        }
+
+        // Entities outside the circle holding `count` of them are further than all of those
+        let mut radius = self.cell_size;
+        loop {
+            let found = self.within_radius(center, radius).count();
+            if found >= count || found == self.len() {
+                break;
+            }
+            radius *= 2.;
+        }
+
+        let mut found: Vec<(f32, UnitId, Entity)> = self
+            .within_radius(center, radius)
+            .map(|entity| {
+                let entry = self.entries[&entity];
+                (entry.position.distance_squared(center), entry.id, entity)
+            })
+            .collect();
+        found.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        found.truncate(count);
+
+        found.into_iter().map(|(.., entity)| entity).collect()
+    }
+}
+
+/// Finds units near a point or in an area from the [`SpatialIndex`], which is up to date at the
+/// start of every simulation tick.
+#[derive(SystemParam)]
+pub struct NearbyUnits<'w> {
+    index: Res<'w, SpatialIndex>,
+}
+
+impl Deref for NearbyUnits<'_> {
+    type Target = SpatialIndex;
+
+    fn deref(&self) -> &SpatialIndex {
+        &self.index
+    }
+}
+
+/// Goes by where the simulation left units rather than their [`Transform`], which is moved every
+/// frame by the interpolation, so that units standing still are not looked at. Units are indexed
+/// from the tick after they are numbered.
+fn update_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    units_query: Query<
+        (Entity, &UnitId, &InterpolatedTranslation),
+        (
+            With<Unit>,
+            Or<(Changed<InterpolatedTranslation>, Added<UnitId>)>,
+        ),
+    >,
+) {
+    for (entity, id, interpolated) in units_query.iter() {
+        index.insert(entity, *id, interpolated.current.xz());
+    }
+}
+
+// An observer rather than `RemovedComponents`, whose events may be gone by the next tick when
+// several frames run without one.
+fn forget_unit(trigger: Trigger<OnRemove, Unit>, mut index: ResMut<SpatialIndex>) {
+    index.remove(trigger.target());
+}
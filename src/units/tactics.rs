@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+
+use crate::{
+    config::{lighting::LightingConfig, tactics::TacticsConfig, weather::WeatherConfig},
+    economy::{Gather, UnitKind},
+    game_states::GameState,
+    input::Action,
+    match_settings::MatchSettings,
+    orders::{LocalCommands, PlayerCommand, UnitId},
+    players::{Owner, Player},
+    simulation::{SimulationSet, SimulationTick},
+    units::{
+        MoveTo, Selected, Unit,
+        combat::{Attack, Weapon},
+        health::{Damage, Health},
+        hero::Hero,
+        spatial::NearbyUnits,
+    },
+    weather::WeatherState,
+};
+
+pub struct TacticsPlugin;
+
+impl Plugin for TacticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Stance>()
+            .register_type::<Anchor>()
+            .register_type::<AutoOrder>()
+            .register_type::<Threat>()
+            .add_systems(
+                Update,
+                cycle_stance.run_if(
+                    in_state(GameState::Playing).and(input_just_pressed(Action::CycleStance)),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    (assign_stances, decide)
+                        .chain()
+                        .in_set(SimulationSet::Tactics),
+                    remember_threats.in_set(SimulationSet::Casualties),
+                ),
+            );
+    }
+}
+
+/// How much a unit is allowed to do on its own while it has no order from its player.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum Stance {
+    /// Picks targets within its acquire range and chases them up to its leash
+    Aggressive,
+    /// Only fights enemies within weapon range or that attacked it
+    Defensive,
+    /// Fights back from where it stands and never moves on its own
+    HoldGround,
+    /// Never fights on its own, but still retreats when hurt
+    Passive,
+}
+
+impl Stance {
+    pub fn next(self) -> Self {
+        match self {
+            Stance::Aggressive => Stance::Defensive,
+            Stance::Defensive => Stance::HoldGround,
+            Stance::HoldGround => Stance::Passive,
+            Stance::Passive => Stance::Aggressive,
+        }
+    }
+}
+
+/// Where a unit was last left by its player, it goes back there once done with what it started
+/// on its own.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Anchor(pub Vec2);
+
+/// Marks the [`MoveTo`] or [`Attack`] of a unit as given by the tactical layer, which may replace
+/// it at any time. Orders without it come from the player and are left alone.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct AutoOrder;
+
+/// The last unit that damaged this one.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component, MapEntities)]
+pub struct Threat(#[entities] pub Entity);
+
+impl MapEntities for Threat {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.0 = entity_mapper.get_mapped(self.0);
+    }
+}
+
+/// What an idle unit can choose to do, the one with the highest utility wins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Behaviour {
+    Engage(Entity),
+    Retreat(Vec2),
+    Return(Vec2),
+    Stay,
+}
+
+/// Units get the stance of their archetype when spawned, loaded units keep the saved one.
+fn assign_stances(
+    mut commands: Commands,
+    units_query: Query<(Entity, &Transform, Has<Hero>), (Added<Unit>, Without<Stance>)>,
+    tactics_config: Res<TacticsConfig>,
+) {
+    for (entity, transform, is_hero) in units_query.iter() {
+        let kind = if is_hero {
+            UnitKind::Hero
+        } else {
+            UnitKind::Soldier
+        };
+
+        commands.entity(entity).insert((
+            tactics_config.archetype(kind).stance,
+            Anchor(transform.translation.xz()),
+        ));
+    }
+}
+
+fn cycle_stance(
+    selected_query: Query<&UnitId, With<Selected>>,
+    mut local_commands: ResMut<LocalCommands>,
+) {
+    local_commands.0.push(PlayerCommand::CycleStance {
+        units: selected_query.iter().copied().collect(),
+    });
+}
+
+fn remember_threats(mut commands: Commands, mut damage_events: EventReader<Damage>) {
+    for damage in damage_events.read() {
+        if let Some(source) = damage.source {
+            // The target may have been killed by this very damage
+            commands.entity(damage.target).try_insert(Threat(source));
+        }
+    }
+}
+
+fn decide(
+    mut commands: Commands,
+    mut units_query: Query<
+        (
+            Entity,
+            &Owner,
+            &Transform,
+            &Health,
+            &Weapon,
+            &Stance,
+            &mut Anchor,
+            Option<&Threat>,
+            Option<&MoveTo>,
+            Option<&Attack>,
+            Has<Gather>,
+            Has<AutoOrder>,
+            Has<Hero>,
+        ),
+        With<Unit>,
+    >,
+    targets_query: Query<(Entity, &UnitId, &Owner, &Transform), With<Unit>>,
+    nearby_units: NearbyUnits,
+    players_query: Query<&Player>,
+    tactics_config: Res<TacticsConfig>,
+    lighting_config: Res<LightingConfig>,
+    weather_config: Res<WeatherConfig>,
+    weather_state: Res<WeatherState>,
+    match_settings: Res<MatchSettings>,
+    tick: Res<SimulationTick>,
+) {
+    let sight = lighting_config.sight_multiplier(tick.0)
+        * weather_config.get(weather_state.current).sight_multiplier;
+    let teams: HashMap<_, _> = players_query
+        .iter()
+        .map(|player| (player.id, player.team))
+        .collect();
+
+    for (
+        entity,
+        owner,
+        transform,
+        health,
+        weapon,
+        stance,
+        mut anchor,
+        threat,
+        move_to,
+        attack,
+        gathering,
+        auto_order,
+        is_hero,
+    ) in units_query.iter_mut()
+    {
+        let position = transform.translation.xz();
+
+        if !auto_order {
+            if move_to.is_some() || attack.is_some() || gathering {
+                // Busy with an order from its player
+                continue;
+            }
+            anchor.0 = position;
+        }
+
+        let kind = if is_hero {
+            UnitKind::Hero
+        } else {
+            UnitKind::Soldier
+        };
+        let archetype = tactics_config.archetype(kind);
+        let home = match_settings.map.start_positions[owner.0.0 as usize];
+        let team = teams.get(&owner.0);
+
+        let mut best = (Behaviour::Stay, 0.);
+        let mut consider = |behaviour: Behaviour, utility: f32| {
+            if utility > best.1 {
+                best = (behaviour, utility);
+            }
+        };
+
+        let health_fraction = health.current / health.max;
+        let hurt = health_fraction < archetype.retreat_health;
+        // Nothing is worth more than staying alive
+        if hurt && *stance != Stance::HoldGround && position.distance(home) > weapon.range {
+            consider(Behaviour::Retreat(home), 1.);
+        }
+
+        if *stance != Stance::Passive {
+            let threat = threat.map(|threat| threat.0);
+            let reach = match stance {
+                Stance::Aggressive => archetype.acquire_range * sight,
+                _ => weapon.range,
+            };
+
+            // The threat is considered even out of reach, it may still be within the leash
+            let candidates = nearby_units
+                .within_radius(position, reach)
+                .filter(|target| Some(*target) != threat)
+                .chain(threat);
+            // Equally good targets go to the lowest id, the index may list them in any order
+            let mut targets: Vec<_> = targets_query.iter_many(candidates).collect();
+            targets.sort_by_key(|(_, id, ..)| **id);
+            for (target, _, target_owner, target_transform) in targets {
+                if teams.get(&target_owner.0) == team {
+                    continue;
+                }
+                let target_position = target_transform.translation.xz();
+                let distance = position.distance(target_position);
+                let is_threat = threat == Some(target);
+
+                let in_reach = match stance {
+                    Stance::HoldGround => distance <= weapon.range,
+                    _ => {
+                        (distance <= reach || is_threat)
+                            && anchor.0.distance(target_position) <= archetype.leash_distance
+                    }
+                };
+                if !in_reach {
+                    continue;
+                }
+
+                // Closer targets are preferred, and hitting back beats anything else in range
+                let closeness = 1. - (distance / reach.max(distance)).min(1.);
+                let utility = 0.4 + 0.3 * closeness + if is_threat { 0.2 } else { 0. };
+                consider(Behaviour::Engage(target), utility);
+            }
+        }
+
+        if !hurt && position.distance(anchor.0) > weapon.range / 2. {
+            consider(Behaviour::Return(anchor.0), 0.2);
+        }
+
+        match best.0 {
+            Behaviour::Engage(target) => {
+                if attack.map(|attack| attack.target) != Some(target) {
+                    commands.entity(entity).insert((
+                        Attack {
+                            target,
+                            hold: *stance == Stance::HoldGround,
+                        },
+                        AutoOrder,
+                    ));
+                }
+            }
+            Behaviour::Retreat(destination) | Behaviour::Return(destination) => {
+                if attack.is_some() || move_to.map(|move_to| move_to.target) != Some(destination) {
+                    commands.entity(entity).remove::<Attack>().insert((
+                        MoveTo {
+                            target: destination,
+                        },
+                        AutoOrder,
+                    ));
+                }
+            }
+            Behaviour::Stay => {
+                if auto_order {
+                    commands
+                        .entity(entity)
+                        .remove::<(Attack, MoveTo, AutoOrder)>();
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::units::Selected;
+use crate::units::{Selected, UnitSelector};
 
 // De-select previously selected units by:
 // 1. Removing Selected component on the unit
@@ -18,3 +18,22 @@ pub fn remove_selection(
         commands.entity(entity).insert(Visibility::Hidden);
     }
 }
+
+// Select a unit by:
+// 1. Adding Selected component on the unit
+// 2. Adding Selected component in its unitSelector and make it Visible
+pub fn add_selection(
+    commands: &mut Commands,
+    unit: Entity,
+    children: Option<&Children>,
+    unit_selectors: &Query<(), With<UnitSelector>>,
+) {
+    commands.entity(unit).insert(Selected);
+    for child in children.into_iter().flatten() {
+        if unit_selectors.contains(*child) {
+            commands
+                .entity(*child)
+                .insert((Visibility::Inherited, Selected));
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::{ecs::system::ScheduleSystem, prelude::*};
+
+use crate::{
+    buildings::Building,
+    config::victory::VictoryConfig,
+    game_states::GameState,
+    match_settings::{MatchSettings, PlayerColor, VictoryCondition},
+    players::{LocalPlayer, Owner, Player, PlayerId, PlayerStats},
+    save::LoadedSave,
+    simulation::SimulationSet,
+    units::{Unit, health::UnitDied},
+};
+
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MatchProgress>()
+            .init_resource::<MatchProgress>()
+            .add_event::<MatchOver>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (
+                    reset_progress.run_if(not(resource_exists::<LoadedSave>)),
+                    spawn_hold_point.run_if(victory_condition_is(VictoryCondition::HoldPoint)),
+                ),
+            )
+            .add_systems(OnExit(GameState::PostGame), forget_result)
+            .configure_sets(
+                FixedUpdate,
+                VictoryChecks
+                    .in_set(SimulationSet::Victory)
+                    .after(tick_clock)
+                    .before(end_match),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    count_casualties.in_set(SimulationSet::Casualties),
+                    (tick_clock, end_match).in_set(SimulationSet::Victory),
+                    // Losing every unit ends the match, unless units can still be trained from
+                    // the buildings left standing
+                    eliminate_all_units
+                        .before(VictoryChecks)
+                        .in_set(SimulationSet::Victory)
+                        .run_if(not(victory_condition_is(
+                            VictoryCondition::DestroyAllBuildings,
+                        ))),
+                ),
+            )
+            .add_victory_condition(VictoryCondition::HoldPoint, hold_point)
+            .add_victory_condition(VictoryCondition::TimeLimit, time_limit)
+            .add_victory_condition(VictoryCondition::DestroyAllBuildings, destroy_all_buildings);
+    }
+}
+
+/// Where the systems checking the chosen [`VictoryCondition`] run, once the clock has ticked and
+/// before the match is ended.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VictoryChecks;
+
+/// Registration point for the ways of winning a match.
+pub trait AddVictoryCondition {
+    /// Runs `checks` on every tick of matches played with `condition`. They send [`MatchOver`]
+    /// once the condition is met.
+    fn add_victory_condition<M>(
+        &mut self,
+        condition: VictoryCondition,
+        checks: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self;
+}
+
+impl AddVictoryCondition for App {
+    fn add_victory_condition<M>(
+        &mut self,
+        condition: VictoryCondition,
+        checks: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        self.add_systems(
+            FixedUpdate,
+            checks
+                .in_set(VictoryChecks)
+                .run_if(victory_condition_is(condition)),
+        )
+    }
+}
+
+/// How far the match went, saved along with it.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct MatchProgress {
+    pub elapsed_seconds: f32,
+    /// Seconds each team held the point for, by team
+    pub held_seconds: BTreeMap<u8, f32>,
+}
+
+/// Sent by a victory condition once it is met. `winning_team` is `None` on a draw.
+#[derive(Event, Debug)]
+pub struct MatchOver {
+    pub winning_team: Option<u8>,
+}
+
+/// A player's line on the end-of-match screen.
+#[derive(Debug)]
+pub struct PlayerSummary {
+    pub name: String,
+    pub team: u8,
+    pub color: PlayerColor,
+    pub stats: PlayerStats,
+    pub score: u32,
+}
+
+/// Outcome of the last match, present while [`GameState::PostGame`] is shown.
+#[derive(Resource, Debug)]
+pub struct MatchResult {
+    pub winning_team: Option<u8>,
+    pub local_team: Option<u8>,
+    pub players: Vec<PlayerSummary>,
+}
+
+pub fn victory_condition_is(
+    condition: VictoryCondition,
+) -> impl FnMut(Res<MatchSettings>) -> bool + Clone {
+    move |match_settings: Res<MatchSettings>| match_settings.victory_condition == condition
+}
+
+fn score(stats: &PlayerStats, victory_config: &VictoryConfig) -> u32 {
+    stats.resources_gathered + stats.units_killed * victory_config.score_per_kill
+}
+
+fn reset_progress(mut commands: Commands) {
+    commands.insert_resource(MatchProgress::default());
+}
+
+fn spawn_hold_point(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    victory_config: Res<VictoryConfig>,
+) {
+    let point = victory_config.hold_point;
+
+    commands.spawn((
+        Name::new("Hold point"),
+        StateScoped(GameState::Playing),
+        Mesh3d(meshes.add(Cylinder::new(victory_config.hold_point_radius, 0.02))),
+        MeshMaterial3d(materials.add(Color::srgba_u8(240, 240, 240, 80))),
+        Transform::from_translation(Vec3::new(point.x, 0.01, point.y)),
+    ));
+}
+
+fn forget_result(mut commands: Commands) {
+    commands.remove_resource::<MatchResult>();
+}
+
+fn tick_clock(mut progress: ResMut<MatchProgress>, time: Res<Time>) {
+    progress.elapsed_seconds += time.delta_secs();
+}
+
+fn count_casualties(
+    mut died_events: EventReader<UnitDied>,
+    mut players_query: Query<(&Player, &mut PlayerStats)>,
+    owners: Query<&Owner>,
+) {
+    for died in died_events.read() {
+        let killer = died
+            .killer
+            .and_then(|killer| owners.get(killer).ok())
+            .map(|owner| owner.0);
+
+        for (player, mut stats) in players_query.iter_mut() {
+            if died.owner == Some(player.id) {
+                stats.units_lost += 1;
+            }
+            if killer == Some(player.id) && died.owner != Some(player.id) {
+                stats.units_killed += 1;
+            }
+        }
+    }
+}
+
+/// Ends the match once a single team, or none, owns any of `owners`.
+fn last_team_standing(
+    owners: impl Iterator<Item = PlayerId>,
+    players_query: &Query<&Player>,
+    match_over: &mut EventWriter<MatchOver>,
+) {
+    let teams: BTreeMap<_, _> = players_query
+        .iter()
+        .map(|player| (player.id, player.team))
+        .collect();
+    // A match played by a single team has no one to eliminate
+    if teams.values().collect::<BTreeSet<_>>().len() < 2 {
+        return;
+    }
+
+    let standing: BTreeSet<u8> = owners
+        .filter_map(|owner| teams.get(&owner).copied())
+        .collect();
+    if standing.len() < 2 {
+        match_over.write(MatchOver {
+            winning_team: standing.first().copied(),
+        });
+    }
+}
+
+fn eliminate_all_units(
+    units_query: Query<&Owner, With<Unit>>,
+    players_query: Query<&Player>,
+    mut match_over: EventWriter<MatchOver>,
+) {
+    last_team_standing(
+        units_query.iter().map(|owner| owner.0),
+        &players_query,
+        &mut match_over,
+    );
+}
+
+fn destroy_all_buildings(
+    buildings_query: Query<&Owner, With<Building>>,
+    players_query: Query<&Player>,
+    mut match_over: EventWriter<MatchOver>,
+) {
+    last_team_standing(
+        buildings_query.iter().map(|owner| owner.0),
+        &players_query,
+        &mut match_over,
+    );
+}
+
+fn hold_point(
+    units_query: Query<(&Owner, &Transform), With<Unit>>,
+    players_query: Query<&Player>,
+    mut progress: ResMut<MatchProgress>,
+    mut match_over: EventWriter<MatchOver>,
+    victory_config: Res<VictoryConfig>,
+    time: Res<Time>,
+) {
+    let radius_squared = victory_config.hold_point_radius.powi(2);
+    let holders: BTreeSet<u8> = units_query
+        .iter()
+        .filter(|(_, transform)| {
+            transform
+                .translation
+                .xz()
+                .distance_squared(victory_config.hold_point)
+                < radius_squared
+        })
+        .filter_map(|(owner, _)| {
+            players_query
+                .iter()
+                .find(|player| player.id == owner.0)
+                .map(|player| player.team)
+        })
+        .collect();
+
+    // Contested points do not count for anyone
+    let holders: Vec<u8> = holders.into_iter().collect();
+    let [team] = holders[..] else {
+        return;
+    };
+
+    let held = progress.held_seconds.entry(team).or_default();
+    *held += time.delta_secs();
+    if *held >= victory_config.hold_point_seconds {
+        match_over.write(MatchOver {
+            winning_team: Some(team),
+        });
+    }
+}
+
+fn time_limit(
+    players_query: Query<(&Player, &PlayerStats)>,
+    progress: Res<MatchProgress>,
+    mut match_over: EventWriter<MatchOver>,
+    victory_config: Res<VictoryConfig>,
+) {
+    if progress.elapsed_seconds < victory_config.time_limit_seconds {
+        return;
+    }
+
+    let mut team_scores: BTreeMap<u8, u32> = BTreeMap::new();
+    for (player, stats) in players_query.iter() {
+        *team_scores.entry(player.team).or_default() += score(stats, &victory_config);
+    }
+
+    let best = team_scores.values().max().copied().unwrap_or_default();
+    let mut leaders = team_scores
+        .into_iter()
+        .filter(|(_, score)| *score == best)
+        .map(|(team, _)| team);
+    let winning_team = match (leaders.next(), leaders.next()) {
+        (Some(team), None) => Some(team),
+        _ => None,
+    };
+
+    match_over.write(MatchOver { winning_team });
+}
+
+fn end_match(
+    mut commands: Commands,
+    mut match_over: EventReader<MatchOver>,
+    players_query: Query<(&Player, &PlayerStats)>,
+    local_player: Res<LocalPlayer>,
+    victory_config: Res<VictoryConfig>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // Several conditions may be met on the same frame, the first one wins
+    let Some(winning_team) = match_over.read().next().map(|over| over.winning_team) else {
+        return;
+    };
+    match_over.clear();
+
+    let mut players_sorted: Vec<_> = players_query.iter().collect();
+    players_sorted.sort_by_key(|(player, _)| player.id);
+    let players: Vec<PlayerSummary> = players_sorted
+        .into_iter()
+        .map(|(player, stats)| PlayerSummary {
+            name: format!("Player {}", player.id.0 + 1),
+            team: player.team,
+            color: player.color,
+            stats: stats.clone(),
+            score: score(stats, &victory_config),
+        })
+        .collect();
+
+    let local_team = players_query
+        .iter()
+        .find(|(player, _)| player.id == local_player.0)
+        .map(|(player, _)| player.team);
+
+    info!("Match over, winning team: {winning_team:?}");
+    commands.insert_resource(MatchResult {
+        winning_team,
+        local_team,
+        players,
+    });
+    next_state.set(GameState::PostGame);
+}
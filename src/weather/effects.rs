@@ -0,0 +1,220 @@
+use bevy::{
+    pbr::{DistanceFog, FogFalloff},
+    prelude::*,
+};
+
+use crate::{
+    camera::MainCamera,
+    config::weather::WeatherConfig,
+    game_states::GameState,
+    simulation::SimulationRng,
+    weather::{Weather, WeatherState},
+};
+
+/// Half of the width of the square around the point the camera looks at where particles fall.
+const AREA_HALF_SIZE: f32 = 12.;
+/// Height above the ground at which particles appear.
+const AREA_HEIGHT: f32 = 8.;
+/// Particles spawned per second while there are fewer than the weather asks for.
+const SPAWN_RATE: f32 = 400.;
+/// Time the fog takes to get most of the way to the density of a new weather.
+const FOG_TRANSITION_SECONDS: f32 = 4.;
+/// Fog distances faded to before removing the fog, far enough to be invisible.
+const CLEAR_FOG_START: f32 = 60.;
+const CLEAR_FOG_END: f32 = 120.;
+
+pub struct WeatherEffectsPlugin;
+
+impl Plugin for WeatherEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup)
+            .add_systems(OnExit(GameState::Playing), remove_fog)
+            .add_systems(
+                Update,
+                (update_fog, spawn_particles, fall).run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Shared by every particle, so that spawning one does not create assets.
+#[derive(Resource)]
+struct ParticleAssets {
+    raindrop_mesh: Handle<Mesh>,
+    raindrop_material: Handle<StandardMaterial>,
+    snowflake_mesh: Handle<Mesh>,
+    snowflake_material: Handle<StandardMaterial>,
+}
+
+/// A raindrop or snowflake, which falls until it reaches the ground.
+#[derive(Component)]
+struct Particle {
+    weather: Weather,
+    velocity: Vec3,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(ParticleAssets {
+        raindrop_mesh: meshes.add(Cuboid::new(0.01, 0.25, 0.01)),
+        raindrop_material: materials.add(StandardMaterial {
+            base_color: Color::srgba(0.6, 0.7, 0.85, 0.5),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        }),
+        snowflake_mesh: meshes.add(Sphere::new(0.03)),
+        snowflake_material: materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+/// Where the camera's line of sight meets the ground.
+fn camera_focus(camera: &GlobalTransform) -> Vec2 {
+    let forward = camera.forward();
+    let position = camera.translation();
+    if forward.y >= 0. {
+        return position.xz();
+    }
+
+    (position - forward * position.y / forward.y).xz()
+}
+
+/// Somewhere in the square around `focus` where particles fall.
+fn random_position(rng: &mut SimulationRng, focus: Vec2) -> Vec2 {
+    let offset = Vec2::new(rng.next_f32(), rng.next_f32()) * 2. - Vec2::ONE;
+    focus + offset * AREA_HALF_SIZE
+}
+
+fn fog_distances(weather: Weather, weather_config: &WeatherConfig) -> Option<(f32, f32)> {
+    let config = weather_config.get(weather);
+    (config.fog_end > 0.).then_some((config.fog_start, config.fog_end))
+}
+
+/// Eases the fog towards the density of the current weather, adding it or removing it as the
+/// weather turns foggy or clear.
+fn update_fog(
+    mut commands: Commands,
+    camera: Single<(Entity, Option<&mut DistanceFog>), With<MainCamera>>,
+    weather_state: Res<WeatherState>,
+    weather_config: Res<WeatherConfig>,
+    time: Res<Time>,
+) {
+    let (camera, fog) = camera.into_inner();
+    let target = fog_distances(weather_state.current, &weather_config);
+    let color = weather_config.get(weather_state.current).fog_color;
+
+    let Some(mut fog) = fog else {
+        if target.is_some() {
+            commands.entity(camera).insert(DistanceFog {
+                color,
+                falloff: FogFalloff::Linear {
+                    start: CLEAR_FOG_START,
+                    end: CLEAR_FOG_END,
+                },
+                ..default()
+            });
+        }
+        return;
+    };
+
+    let FogFalloff::Linear { start, end } = &fog.falloff else {
+        return;
+    };
+    let (target_start, target_end) = target.unwrap_or((CLEAR_FOG_START, CLEAR_FOG_END));
+    let step = (time.delta_secs() / FOG_TRANSITION_SECONDS).min(1.);
+    let start = start.lerp(target_start, step);
+    let end = end.lerp(target_end, step);
+
+    if target.is_none() && start > CLEAR_FOG_START - 1. {
+        commands.entity(camera).remove::<DistanceFog>();
+        return;
+    }
+
+    fog.falloff = FogFalloff::Linear { start, end };
+    fog.color = fog.color.mix(&color, step);
+}
+
+fn remove_fog(mut commands: Commands, camera: Single<Entity, With<MainCamera>>) {
+    commands.entity(*camera).remove::<DistanceFog>();
+}
+
+/// Tops up the particles of the current weather, a few at a time so that rain and snow build up.
+fn spawn_particles(
+    mut commands: Commands,
+    camera: Single<&GlobalTransform, With<MainCamera>>,
+    particles_query: Query<&Particle>,
+    assets: Res<ParticleAssets>,
+    weather_state: Res<WeatherState>,
+    weather_config: Res<WeatherConfig>,
+    time: Res<Time>,
+    // Its own generator, drawing from the simulation's would desynchronise the match
+    mut rng: Local<SimulationRng>,
+) {
+    let weather = weather_state.current;
+    let (mesh, material) = match weather {
+        Weather::Rain => (&assets.raindrop_mesh, &assets.raindrop_material),
+        Weather::Snow => (&assets.snowflake_mesh, &assets.snowflake_material),
+        Weather::Clear | Weather::Fog => return,
+    };
+
+    let wanted = weather_config.get(weather).particles;
+    let existing = particles_query
+        .iter()
+        .filter(|particle| particle.weather == weather)
+        .count();
+    let missing = wanted.saturating_sub(existing);
+    let count = missing.min((SPAWN_RATE * time.delta_secs()).ceil() as usize);
+
+    let focus = camera_focus(&camera);
+    for _ in 0..count {
+        let position = random_position(&mut rng, focus);
+        let height = rng.next_f32() * AREA_HEIGHT;
+        let velocity = match weather {
+            Weather::Rain => Vec3::new(0.5, -12., 0.),
+            _ => Vec3::new(rng.next_f32() - 0.5, -1., rng.next_f32() - 0.5) * 0.8,
+        };
+
+        commands.spawn((
+            Name::new("WeatherParticle"),
+            StateScoped(GameState::Playing),
+            Particle { weather, velocity },
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(position.x, height, position.y),
+        ));
+    }
+}
+
+/// Particles that reach the ground start over from the top around the camera, unless the weather
+/// they belong to is over.
+fn fall(
+    mut commands: Commands,
+    camera: Single<&GlobalTransform, With<MainCamera>>,
+    mut particles_query: Query<(Entity, &Particle, &mut Transform)>,
+    weather_state: Res<WeatherState>,
+    time: Res<Time>,
+    mut rng: Local<SimulationRng>,
+) {
+    let focus = camera_focus(&camera);
+
+    for (entity, particle, mut transform) in particles_query.iter_mut() {
+        transform.translation += particle.velocity * time.delta_secs();
+        if transform.translation.y > 0. {
+            continue;
+        }
+
+        if particle.weather != weather_state.current {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let position = random_position(&mut rng, focus);
+        transform.translation = Vec3::new(position.x, AREA_HEIGHT, position.y);
+    }
+}
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    config::weather::WeatherConfig,
+    game_states::GameState,
+    match_settings::MatchSettings,
+    save::LoadedSave,
+    simulation::{SimulationRng, SimulationSet, SimulationTick, TICKS_PER_SECOND},
+};
+
+pub mod effects;
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Weather>()
+            .register_type::<WeatherState>()
+            .init_resource::<WeatherState>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                reset_weather.run_if(not(resource_exists::<LoadedSave>)),
+            )
+            .add_systems(
+                FixedUpdate,
+                update_weather.in_set(SimulationSet::Environment),
+            );
+    }
+}
+
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Fog,
+    Snow,
+}
+
+/// How the weather of a map changes over a match.
+#[derive(Reflect, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WeatherSchedule {
+    /// Picked with the weights of [`WeatherConfig`], for a random duration each time
+    #[default]
+    Random,
+    /// Changes at set times, clear until the first one
+    Triggers(Vec<WeatherTrigger>),
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherTrigger {
+    /// Time since the start of the match
+    pub at_seconds: f32,
+    pub weather: Weather,
+}
+
+impl WeatherTrigger {
+    fn tick(&self) -> u64 {
+        (self.at_seconds as f64 * TICKS_PER_SECOND) as u64
+    }
+}
+
+/// The weather of the match, saved along with it.
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct WeatherState {
+    pub current: Weather,
+    /// Tick at which random weather changes, unused on maps with triggers
+    pub next_change: u64,
+}
+
+fn reset_weather(mut commands: Commands) {
+    commands.insert_resource(WeatherState::default());
+}
+
+fn random_weather(rng: &mut SimulationRng, config: &WeatherConfig) -> Option<Weather> {
+    let total: u32 = Weather::iter()
+        .map(|weather| config.get(weather).weight)
+        .sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = rng.below(total);
+    Weather::iter().find(|weather| {
+        let weight = config.get(*weather).weight;
+        if roll < weight {
+            true
+        } else {
+            roll -= weight;
+            false
+        }
+    })
+}
+
+fn update_weather(
+    mut weather_state: ResMut<WeatherState>,
+    mut rng: ResMut<SimulationRng>,
+    match_settings: Res<MatchSettings>,
+    weather_config: Res<WeatherConfig>,
+    tick: Res<SimulationTick>,
+) {
+    match &match_settings.map.weather {
+        WeatherSchedule::Triggers(triggers) => {
+            // The latest trigger wins, whatever order the map lists them in
+            let weather = triggers
+                .iter()
+                .filter(|trigger| trigger.tick() <= tick.0)
+                .max_by_key(|trigger| trigger.tick())
+                .map_or(Weather::Clear, |trigger| trigger.weather);
+
+            if weather_state.current != weather {
+                weather_state.current = weather;
+            }
+        }
+        WeatherSchedule::Random => {
+            if tick.0 < weather_state.next_change {
+                return;
+            }
+
+            let config = &*weather_config;
+            let weather = random_weather(&mut rng, config).unwrap_or_default();
+            let seconds = config.min_duration_seconds
+                + rng.next_f32() * (config.max_duration_seconds - config.min_duration_seconds);
+
+            weather_state.current = weather;
+            // At least a tick, so that the rng is not drawn from every tick
+            weather_state.next_change =
+                tick.0 + ((seconds as f64 * TICKS_PER_SECOND) as u64).max(1);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+mod support;
+
+use bevy::prelude::*;
+use rts_game_rs::{
+    orders::{CommandTarget, LocalCommands, PlayerCommand, UnitId},
+    players::Owner,
+    units::{health::Health, hero::Hero},
+};
+use support::TestGame;
+
+const STRIKE: usize = 0;
+const BLINK: usize = 1;
+
+/// The hero of the local player, once it is numbered.
+fn hero(game: &mut TestGame) -> (Entity, UnitId) {
+    game.advance_ticks(1);
+    let local_player = game.local_player();
+    game.world_mut()
+        .query_filtered::<(Entity, &UnitId, &Owner), With<Hero>>()
+        .iter(game.world())
+        .find(|(.., owner)| owner.0 == local_player)
+        .map(|(hero, id, _)| (hero, *id))
+        .expect("the local player should start with a hero")
+}
+
+fn cast(game: &mut TestGame, caster: UnitId, ability: usize, target: CommandTarget) {
+    game.world_mut()
+        .resource_mut::<LocalCommands>()
+        .0
+        .push(PlayerCommand::CastAbility {
+            caster,
+            ability,
+            target,
+        });
+    game.advance_ticks(1);
+}
+
+#[test]
+fn strike_only_hits_units_in_range() {
+    let mut game = TestGame::new();
+    let (hero, caster) = hero(&mut game);
+    let position = game.position(hero);
+    let local_player = game.local_player();
+    let near = game.spawn_soldier(local_player, position + Vec2::new(1.5, 0.));
+    let far = game.spawn_soldier(local_player, position + Vec2::new(0., 8.));
+    game.advance_ticks(1);
+    let near_id = *game.get::<UnitId>(near);
+    let far_id = *game.get::<UnitId>(far);
+    let full_health = game.get::<Health>(far).current;
+
+    cast(&mut game, caster, STRIKE, CommandTarget::Unit(far_id));
+    assert_eq!(game.get::<Health>(far).current, full_health);
+
+    cast(&mut game, caster, STRIKE, CommandTarget::Unit(near_id));
+    assert!(game.get::<Health>(near).current < full_health);
+}
+
+#[test]
+fn blink_only_reaches_points_in_range() {
+    let mut game = TestGame::new();
+    let (hero, caster) = hero(&mut game);
+    let start = game.position(hero);
+    // Towards the middle of the map, so that both points are on the ground
+    let direction = -start.normalize();
+
+    cast(
+        &mut game,
+        caster,
+        BLINK,
+        CommandTarget::Point(start + direction * 12.),
+    );
+    assert_eq!(game.position(hero), start);
+
+    let target = start + direction * 4.;
+    cast(&mut game, caster, BLINK, CommandTarget::Point(target));
+    assert!(game.position(hero).distance(target) < 0.01);
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use rts_game_rs::units::animation::{AnimationState, UnitActivity, choose_animation};
+
+const WALK_SPEED: f32 = 1.;
+
+fn activity(motion: Vec2, target: Option<Vec2>) -> UnitActivity {
+    UnitActivity {
+        dead: false,
+        motion,
+        target,
+        speed: 1.,
+    }
+}
+
+#[test]
+fn idle_units_play_idle() {
+    let (state, _) = choose_animation(&activity(Vec2::ZERO, None), WALK_SPEED);
+
+    assert_eq!(state, AnimationState::Idle);
+}
+
+#[test]
+fn moving_units_walk_even_when_chasing_a_target() {
+    let (state, _) = choose_animation(
+        &activity(Vec2::new(0.05, 0.), Some(Vec2::new(3., 0.))),
+        WALK_SPEED,
+    );
+
+    assert_eq!(state, AnimationState::Walk);
+}
+
+#[test]
+fn units_standing_by_their_target_attack() {
+    let (state, _) = choose_animation(&activity(Vec2::ZERO, Some(Vec2::X)), WALK_SPEED);
+
+    assert_eq!(state, AnimationState::Attack);
+}
+
+#[test]
+fn corpses_die_whatever_they_were_doing() {
+    let mut dead = activity(Vec2::X, Some(Vec2::X));
+    dead.dead = true;
+
+    assert_eq!(choose_animation(&dead, WALK_SPEED).0, AnimationState::Die);
+}
+
+#[test]
+fn walk_is_played_at_the_speed_of_the_unit() {
+    let mut slowed = activity(Vec2::X, None);
+    // A unit of speed 1.5 slowed down to three quarters by snow
+    slowed.speed = 1.5 * 0.75;
+
+    let (_, speed) = choose_animation(&slowed, 0.5);
+
+    assert!((speed - 2.25).abs() < 1e-6);
+}
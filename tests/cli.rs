@@ -0,0 +1,118 @@
+use std::{env, fs};
+
+use rts_game_rs::{
+    cli::LaunchOptions,
+    game_states::GameState,
+    match_settings::{MatchSettings, SlotKind},
+    replay::Replay,
+};
+
+fn parse(args: &[&str]) -> anyhow::Result<LaunchOptions> {
+    LaunchOptions::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+fn kinds(settings: &MatchSettings) -> Vec<SlotKind> {
+    settings.slots.iter().map(|slot| slot.kind).collect()
+}
+
+#[test]
+fn flags_are_applied() {
+    let options = parse(&[
+        "--state",
+        "options",
+        "--map",
+        "valley",
+        "--seed",
+        "42",
+        "--players",
+        "ai, human",
+        "--ticks",
+        "100",
+        "--stress",
+        "500",
+    ])
+    .unwrap();
+
+    assert_eq!(options.state, GameState::Options);
+    assert_eq!(options.match_settings.map.name, "Valley");
+    assert_eq!(options.match_settings.seed, 42);
+    assert_eq!(
+        kinds(&options.match_settings),
+        [
+            SlotKind::Ai,
+            SlotKind::Human,
+            SlotKind::Closed,
+            SlotKind::Closed
+        ]
+    );
+    assert_eq!(options.ticks, 100);
+    assert_eq!(options.stress_units, Some(500));
+    assert!(!options.headless);
+    assert!(!options.help);
+}
+
+#[test]
+fn bad_values_are_refused() {
+    for args in [
+        &["--seed", "tomorrow"][..],
+        &["--ticks", "-1"],
+        &["--state", "lobby"],
+        &["--map", "no such map"],
+        &["--players", "human,robot"],
+        &["--players", "ai,ai,ai,ai,ai"],
+        &["--replay", "no such replay"],
+        &["--seed"],
+        &["--fast"],
+    ] {
+        assert!(parse(args).is_err(), "{args:?} should be refused");
+    }
+}
+
+#[test]
+fn replays_are_played_as_recorded() {
+    let settings = MatchSettings {
+        seed: 7,
+        ..MatchSettings::default()
+    };
+    let path = env::temp_dir().join(format!("rts-cli-test-{}.replay.json", std::process::id()));
+    Replay::new(settings).save(&path).unwrap();
+
+    let options = parse(&[
+        "--state",
+        "credits",
+        "--seed",
+        "42",
+        "--players",
+        "closed",
+        "--replay",
+        path.to_str().unwrap(),
+    ]);
+    fs::remove_file(&path).unwrap();
+    let options = options.unwrap();
+
+    assert_eq!(options.state, GameState::Playing);
+    assert_eq!(options.match_settings.seed, 7);
+    assert_eq!(
+        kinds(&options.match_settings),
+        kinds(&MatchSettings::default())
+    );
+    assert_eq!(options.scenario().settings.seed, 7);
+}
+
+#[test]
+fn headless_runs_replace_humans_with_computer_players() {
+    let options = parse(&["--headless", "--players", "human,ai,closed"]).unwrap();
+
+    assert!(options.headless);
+    assert_eq!(
+        kinds(&options.scenario().settings),
+        [
+            SlotKind::Ai,
+            SlotKind::Ai,
+            SlotKind::Closed,
+            SlotKind::Closed
+        ]
+    );
+    // The settings shown to a window stay as given
+    assert_eq!(options.match_settings.slots[0].kind, SlotKind::Human);
+}
@@ -0,0 +1,73 @@
+mod support;
+
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use rts_game_rs::{
+    match_settings::{MatchSettings, SlotKind},
+    orders::UnitId,
+    players::{Player, PlayerId, PlayerStats},
+    units::{
+        combat::Attack, health::Health, rendering::UnitAssets, spawn_soldier, tactics::Stance,
+    },
+};
+use support::TestGame;
+
+fn kills(game: &mut TestGame, player: PlayerId) -> u32 {
+    game.world_mut()
+        .query::<(&Player, &PlayerStats)>()
+        .iter(game.world())
+        .find(|(owner, _)| owner.id == player)
+        .map(|(_, stats)| stats.units_killed)
+        .expect("the player should be in the match")
+}
+
+#[test]
+fn kill_goes_to_the_lowest_id_whatever_the_spawn_order() {
+    let mut settings = MatchSettings::default();
+    for slot in settings.slots.iter_mut().take(3) {
+        slot.kind = SlotKind::Human;
+    }
+    let mut game = TestGame::with_settings(settings);
+    let target = game.spawn_soldier(PlayerId(1), Vec2::ZERO);
+    // Spawned on the same tick, the first one is numbered after the other as units are numbered
+    // by owner
+    let (late, early) = game
+        .world_mut()
+        .run_system_once(
+            |mut commands: Commands, mut assets: UnitAssets, players_query: Query<&Player>| {
+                let mut spawn = |owner: PlayerId, position: Vec2| {
+                    let player = players_query
+                        .iter()
+                        .find(|player| player.id == owner)
+                        .unwrap();
+                    spawn_soldier(&mut commands, &mut assets, player, position)
+                };
+                (
+                    spawn(PlayerId(2), Vec2::new(0.3, 0.)),
+                    spawn(PlayerId(0), Vec2::new(-0.3, 0.)),
+                )
+            },
+        )
+        .unwrap();
+    game.advance_ticks(1);
+    assert!(game.get::<UnitId>(early) < game.get::<UnitId>(late));
+
+    // Left alone, the nearly dead target would run home
+    game.world_mut().entity_mut(target).insert((
+        Health {
+            current: 1.,
+            max: 100.,
+        },
+        Stance::HoldGround,
+    ));
+    for attacker in [late, early] {
+        game.world_mut()
+            .entity_mut(attacker)
+            .insert(Attack { target, hold: true });
+    }
+    // Both fired as soon as they saw an enemy, their weapons come back on the same tick
+    game.advance_seconds(1.5);
+
+    assert!(game.world().get_entity(target).is_err());
+    assert_eq!(kills(&mut game, PlayerId(0)), 1);
+    assert_eq!(kills(&mut game, PlayerId(2)), 0);
+}
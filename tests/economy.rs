@@ -0,0 +1,88 @@
+mod support;
+
+use bevy::prelude::*;
+use rts_game_rs::{
+    config::economy::EconomyConfig,
+    economy::{Gather, ResourceNode},
+    players::{Player, PlayerStats},
+};
+use support::TestGame;
+
+/// A match where resources only come from gatherers.
+fn game_without_income() -> TestGame {
+    let mut game = TestGame::new();
+    game.world_mut()
+        .resource_mut::<EconomyConfig>()
+        .income_per_second = 0.;
+    game
+}
+
+fn mine(game: &mut TestGame) -> Vec2 {
+    game.world_mut()
+        .query_filtered::<&Transform, With<ResourceNode>>()
+        .single(game.world())
+        .expect("the local player should have a mine")
+        .translation
+        .xz()
+}
+
+fn resources(game: &mut TestGame) -> (u32, u32) {
+    let (player, stats) = game
+        .world_mut()
+        .query::<(&Player, &PlayerStats)>()
+        .single(game.world())
+        .expect("the local player should be alone");
+    (player.resources, stats.resources_gathered)
+}
+
+#[test]
+fn only_open_slots_get_a_mine() {
+    let mut game = TestGame::new();
+
+    let mines = game
+        .world_mut()
+        .query_filtered::<(), With<ResourceNode>>()
+        .iter(game.world())
+        .count();
+
+    assert_eq!(mines, 1);
+}
+
+#[test]
+fn gatherers_carry_loads_from_their_mine_home() {
+    let mut game = game_without_income();
+    let mine = mine(&mut game);
+    let unit = game.spawn_soldier(game.local_player(), mine);
+    let (resources_before, gathered_before) = resources(&mut game);
+    let load = game.world().resource::<EconomyConfig>().gather_amount;
+
+    game.left_click(unit);
+    game.right_click_ground(mine + Vec2::new(0.2, 0.));
+    game.advance_ticks(1);
+    assert_eq!(game.get::<Gather>(unit).node, mine);
+
+    // Two seconds of mining and a walk of one and a half units home, at one unit per second
+    game.advance_seconds(4.);
+
+    assert_eq!(
+        resources(&mut game),
+        (resources_before + load, gathered_before + load)
+    );
+    // Straight back to work
+    assert_eq!(game.get::<Gather>(unit).carrying, 0);
+}
+
+#[test]
+fn moving_a_gatherer_stops_it_gathering() {
+    let mut game = game_without_income();
+    let mine = mine(&mut game);
+    let unit = game.spawn_soldier(game.local_player(), mine);
+
+    game.left_click(unit);
+    game.right_click_ground(mine);
+    game.advance_ticks(1);
+    game.right_click_ground(Vec2::ZERO);
+    game.advance_ticks(1);
+
+    assert!(!game.has::<Gather>(unit));
+}
@@ -0,0 +1,35 @@
+mod support;
+
+use bevy::prelude::*;
+use rts_game_rs::input::Action;
+use support::TestGame;
+
+fn pressed(game: &TestGame, action: Action) -> bool {
+    game.world()
+        .resource::<ButtonInput<Action>>()
+        .pressed(action)
+}
+
+#[test]
+fn escape_alone_cancels_abilities() {
+    let mut game = TestGame::new();
+
+    game.press_key(KeyCode::Escape);
+    game.advance_ticks(1);
+
+    assert!(pressed(&game, Action::CancelAbility));
+    assert!(!pressed(&game, Action::ExitGame));
+}
+
+#[test]
+fn longest_chord_held_wins() {
+    let mut game = TestGame::new();
+
+    // Escape on its own is also a chord, bound to cancelling abilities
+    game.press_key(KeyCode::SuperLeft);
+    game.press_key(KeyCode::Escape);
+    game.advance_ticks(1);
+
+    assert!(pressed(&game, Action::ExitGame));
+    assert!(!pressed(&game, Action::CancelAbility));
+}
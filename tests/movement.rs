@@ -0,0 +1,81 @@
+mod support;
+
+use bevy::prelude::*;
+use rts_game_rs::{
+    orders::{PlayerCommand, ScheduledCommands, UnitId},
+    simulation::SimulationTick,
+    units::{MoveTo, tactics::Stance},
+};
+use support::TestGame;
+
+#[test]
+fn commanded_unit_arrives_at_destination() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+    let destination = Vec2::new(1., -2.);
+
+    game.left_click(unit);
+    game.right_click_ground(destination);
+    game.advance_ticks(1);
+    assert!(game.has::<MoveTo>(unit));
+
+    // Three units away at one unit per second
+    game.advance_seconds(3.5);
+
+    assert!(!game.has::<MoveTo>(unit));
+    assert_eq!(game.position(unit), destination);
+}
+
+#[test]
+fn unselected_units_ignore_commands() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+
+    game.right_click_ground(Vec2::new(1., -2.));
+    game.advance_seconds(1.);
+
+    assert!(!game.has::<MoveTo>(unit));
+    assert_eq!(game.position(unit), Vec2::new(-2., -2.));
+}
+
+#[test]
+fn stance_key_cycles_the_stance_of_selected_units() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+    let stance = *game.get::<Stance>(unit);
+
+    game.left_click(unit);
+    game.press_key(KeyCode::KeyG);
+    // The key is read on the first update, its command played on the next tick
+    game.advance_ticks(2);
+    game.release_key(KeyCode::KeyG);
+
+    assert_eq!(*game.get::<Stance>(unit), stance.next());
+}
+
+#[test]
+fn commands_of_ticks_already_played_are_dropped() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+    let id = *game.get::<UnitId>(unit);
+    let local_player = game.local_player();
+    let tick = game.world().resource::<SimulationTick>().0;
+
+    game.world_mut()
+        .resource_mut::<ScheduledCommands>()
+        .0
+        .insert(
+            tick - 1,
+            vec![(
+                local_player,
+                PlayerCommand::Move {
+                    units: vec![id],
+                    target: Vec2::new(1., -2.),
+                },
+            )],
+        );
+    game.advance_ticks(1);
+
+    assert!(!game.has::<MoveTo>(unit));
+    assert!(game.world().resource::<ScheduledCommands>().0.is_empty());
+}
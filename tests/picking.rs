@@ -0,0 +1,148 @@
+mod support;
+
+use bevy::{
+    ecs::event::Events,
+    picking::{
+        backend::{
+            PointerHits,
+            ray::{RayId, RayMap},
+        },
+        pointer::PointerId,
+    },
+    prelude::*,
+};
+use rts_game_rs::{
+    camera::MainCamera,
+    config::picking::PickingConfig,
+    picking::{PickShape, PickingBackendPlugin, Picks, UNIT_ORDER_OFFSET, pick},
+};
+use support::TestGame;
+
+const PICK_RADIUS: f32 = 0.1;
+
+/// Casts a ray from straight above `point`, as a top down camera would.
+fn pick_at(game: &mut TestGame, point: Vec2) -> Picks {
+    let ray = Ray3d::new(Vec3::new(point.x, 10., point.y), Dir3::NEG_Y);
+    let shapes: Vec<(Entity, PickShape, Vec3)> = game
+        .world_mut()
+        .query::<(Entity, &PickShape, &GlobalTransform)>()
+        .iter(game.world())
+        .map(|(entity, shape, transform)| (entity, *shape, transform.translation()))
+        .collect();
+
+    pick(
+        ray,
+        shapes
+            .iter()
+            .map(|(entity, shape, center)| (*entity, shape, *center)),
+        PICK_RADIUS,
+    )
+}
+
+#[test]
+fn units_are_picked_over_the_terrain() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+    let terrain = game.terrain();
+
+    let picks = pick_at(&mut game, Vec2::new(-2., -2.));
+
+    assert_eq!(picks.unit.map(|(entity, _)| entity), Some(unit));
+    assert_eq!(picks.ground.map(|(entity, _)| entity), Some(terrain));
+    let (_, unit_depth) = picks.unit.unwrap();
+    let (_, ground_depth) = picks.ground.unwrap();
+    assert!(unit_depth < ground_depth);
+}
+
+#[test]
+fn pick_radius_widens_thin_units() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+
+    // Soldiers are 0.1 wide, the pick radius adds as much again
+    let near = pick_at(&mut game, Vec2::new(-1.85, -2.));
+    let away = pick_at(&mut game, Vec2::new(-1.75, -2.));
+
+    assert_eq!(near.unit.map(|(entity, _)| entity), Some(unit));
+    assert_eq!(away.unit, None);
+    assert!(away.ground.is_some());
+}
+
+#[test]
+fn the_unit_closest_to_the_pointer_wins() {
+    let mut game = TestGame::new();
+    let left = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+    let right = game.spawn_soldier(game.local_player(), Vec2::new(-1.7, -2.));
+
+    let picks = pick_at(&mut game, Vec2::new(-1.8, -2.));
+
+    assert_eq!(picks.unit.map(|(entity, _)| entity), Some(right));
+    assert_ne!(picks.unit.map(|(entity, _)| entity), Some(left));
+}
+
+#[test]
+fn nothing_is_picked_off_the_map() {
+    let mut game = TestGame::new();
+
+    assert_eq!(pick_at(&mut game, Vec2::new(50., 50.)), Picks::default());
+}
+
+#[test]
+fn backend_reports_units_above_the_terrain() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, PickingBackendPlugin))
+        .init_resource::<RayMap>()
+        .add_event::<PointerHits>()
+        .insert_resource(PickingConfig {
+            unit_pick_radius: PICK_RADIUS,
+        });
+
+    let world = app.world_mut();
+    let camera = world
+        .spawn((
+            MainCamera,
+            Camera {
+                order: 1,
+                ..default()
+            },
+        ))
+        .id();
+    let unit = world
+        .spawn((
+            PickShape::Unit {
+                radius: 0.1,
+                half_height: 0.15,
+            },
+            GlobalTransform::from_translation(Vec3::Y * 0.3),
+            InheritedVisibility::VISIBLE,
+        ))
+        .id();
+    let terrain = world
+        .spawn((
+            PickShape::Ground {
+                half_size: Vec2::splat(5.),
+            },
+            GlobalTransform::IDENTITY,
+            InheritedVisibility::VISIBLE,
+        ))
+        .id();
+    world.resource_mut::<RayMap>().map.insert(
+        RayId::new(camera, PointerId::Mouse),
+        Ray3d::new(Vec3::Y * 10., Dir3::NEG_Y),
+    );
+
+    app.update();
+
+    let events = app.world().resource::<Events<PointerHits>>();
+    let hits: Vec<&PointerHits> = events.iter_current_update_events().collect();
+    let order_of = |entity: Entity| {
+        hits.iter()
+            .find(|hits| hits.picks.iter().any(|(picked, _)| *picked == entity))
+            .map(|hits| hits.order)
+    };
+
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|hits| hits.pointer == PointerId::Mouse));
+    assert_eq!(order_of(unit), Some(1. + UNIT_ORDER_OFFSET));
+    assert_eq!(order_of(terrain), Some(1.));
+}
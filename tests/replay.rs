@@ -0,0 +1,41 @@
+mod support;
+
+use bevy::prelude::*;
+use rts_game_rs::{
+    headless::run_scenario, match_settings::MatchSettings, orders::UnitId, players::Owner,
+    replay::ReplayRecorder, units::Unit,
+};
+use support::TestGame;
+
+#[test]
+fn recorded_match_plays_back_to_the_same_state() {
+    // The computer opponent gives orders of its own, recorded along with the player's
+    let mut game = TestGame::with_settings(MatchSettings::default());
+    let local_player = game.local_player();
+    // Starting units can only be commanded once they are numbered
+    game.advance_ticks(1);
+    let unit = game
+        .world_mut()
+        .query_filtered::<(Entity, &Owner), (With<Unit>, With<UnitId>)>()
+        .iter(game.world())
+        .find(|(_, owner)| owner.0 == local_player)
+        .map(|(unit, _)| unit)
+        .expect("the local player should start with units");
+
+    game.left_click(unit);
+    game.right_click_ground(Vec2::new(1., 1.));
+    game.advance_seconds(3.);
+
+    let replay = game.world().resource::<ReplayRecorder>().0.clone();
+    assert!(
+        replay
+            .commands
+            .iter()
+            .any(|recorded| recorded.player != local_player)
+    );
+
+    let report = run_scenario(replay.clone(), replay.ticks);
+
+    assert_eq!(report.ticks, replay.ticks);
+    assert_eq!(report.checksum, replay.checksum);
+}
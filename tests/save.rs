@@ -0,0 +1,58 @@
+mod support;
+
+use std::{env, fs};
+
+use bevy::prelude::*;
+use rts_game_rs::{
+    match_settings::{MatchSettings, SlotKind},
+    orders::UnitId,
+    save::write_save,
+    units::{MoveTo, combat::Attack, tactics::Threat},
+};
+use support::TestGame;
+
+fn unit_with_id(game: &mut TestGame, id: UnitId) -> Entity {
+    game.world_mut()
+        .query::<(Entity, &UnitId)>()
+        .iter(game.world())
+        .find(|(_, unit_id)| **unit_id == id)
+        .map(|(entity, _)| entity)
+        .expect("the unit should have been loaded")
+}
+
+#[test]
+fn units_keep_fighting_after_a_save_is_loaded() {
+    let mut settings = MatchSettings::default();
+    for slot in settings.slots.iter_mut().skip(1) {
+        slot.kind = SlotKind::Closed;
+    }
+    let mut game = TestGame::with_settings(settings.clone());
+    let local_player = game.local_player();
+    let attacker = game.spawn_soldier(local_player, Vec2::new(-2., -2.));
+    let target = game.spawn_soldier(local_player, Vec2::new(2., -2.));
+    game.advance_ticks(1);
+    game.world_mut().entity_mut(attacker).insert(Attack {
+        target,
+        hold: false,
+    });
+    game.world_mut().entity_mut(target).insert(Threat(attacker));
+    game.advance_ticks(1);
+    let attacker_id = *game.get::<UnitId>(attacker);
+    let target_id = *game.get::<UnitId>(target);
+
+    let path = env::temp_dir().join(format!("rts-save-test-{}.scn.ron", std::process::id()));
+    write_save(game.world_mut(), &path).expect("the match should be saved");
+    let mut loaded = TestGame::load(settings, path.clone());
+    fs::remove_file(&path).expect("the save should have been written");
+
+    let attacker = unit_with_id(&mut loaded, attacker_id);
+    let target = unit_with_id(&mut loaded, target_id);
+    assert_eq!(loaded.get::<Attack>(attacker).target, target);
+    assert_eq!(loaded.get::<Threat>(target).0, attacker);
+
+    // Still chasing its target, which is out of reach
+    let start = loaded.position(attacker);
+    loaded.advance_seconds(1.);
+    assert!(loaded.has::<MoveTo>(attacker));
+    assert!(loaded.position(attacker).x > start.x);
+}
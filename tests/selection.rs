@@ -0,0 +1,39 @@
+mod support;
+
+use bevy::prelude::*;
+use rts_game_rs::units::Selected;
+use support::TestGame;
+
+#[test]
+fn clicking_own_unit_selects_it() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+
+    game.left_click(unit);
+
+    assert!(game.has::<Selected>(unit));
+}
+
+#[test]
+fn clicking_another_unit_moves_the_selection() {
+    let mut game = TestGame::new();
+    let first = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+    let second = game.spawn_soldier(game.local_player(), Vec2::new(2., -2.));
+
+    game.left_click(first);
+    game.left_click(second);
+
+    assert!(!game.has::<Selected>(first));
+    assert!(game.has::<Selected>(second));
+}
+
+#[test]
+fn clicking_the_terrain_clears_the_selection() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+
+    game.left_click(unit);
+    game.left_click_ground(Vec2::new(1., 1.));
+
+    assert!(!game.has::<Selected>(unit));
+}
@@ -0,0 +1,185 @@
+mod support;
+
+use bevy::prelude::*;
+use rts_game_rs::{orders::UnitId, simulation::SimulationRng, units::spatial::SpatialIndex};
+use support::TestGame;
+
+const HALF_SIZE: f32 = 15.;
+
+fn random_position(rng: &mut SimulationRng) -> Vec2 {
+    (Vec2::new(rng.next_f32(), rng.next_f32()) * 2. - Vec2::ONE) * HALF_SIZE
+}
+
+/// An index of scattered entities, along with where each of them is.
+fn scattered(count: u32) -> (SpatialIndex, Vec<(Entity, Vec2)>) {
+    let mut rng = SimulationRng::from_seed(7);
+    let mut index = SpatialIndex::default();
+    let entities: Vec<(Entity, Vec2)> = (0..count)
+        .map(|id| (Entity::from_raw(id), random_position(&mut rng)))
+        .collect();
+    for (entity, position) in &entities {
+        index.insert(*entity, id(*entity), *position);
+    }
+
+    (index, entities)
+}
+
+/// The entities of these tests are numbered after their index.
+fn id(entity: Entity) -> UnitId {
+    UnitId(entity.index())
+}
+
+fn sorted(mut entities: Vec<Entity>) -> Vec<Entity> {
+    entities.sort();
+    entities
+}
+
+#[test]
+fn radius_and_rect_queries_match_a_linear_scan() {
+    let (index, entities) = scattered(500);
+    let center = Vec2::new(1.5, -2.);
+
+    let near = sorted(index.within_radius(center, 3.).collect());
+    let expected = sorted(
+        entities
+            .iter()
+            .filter(|(_, position)| position.distance(center) <= 3.)
+            .map(|(entity, _)| *entity)
+            .collect(),
+    );
+    assert_eq!(near, expected);
+
+    let area = Rect::new(-4., -1., 2.5, 6.);
+    let inside = sorted(index.within_rect(area).collect());
+    let expected = sorted(
+        entities
+            .iter()
+            .filter(|(_, position)| area.contains(*position))
+            .map(|(entity, _)| *entity)
+            .collect(),
+    );
+    assert_eq!(inside, expected);
+}
+
+#[test]
+fn nearest_are_the_closest_in_order() {
+    let (index, entities) = scattered(500);
+    let center = Vec2::new(-7., 3.);
+
+    let mut expected = entities.clone();
+    expected.sort_by(|a, b| {
+        a.1.distance_squared(center)
+            .total_cmp(&b.1.distance_squared(center))
+            .then(id(a.0).cmp(&id(b.0)))
+    });
+    let expected: Vec<Entity> = expected
+        .iter()
+        .take(10)
+        .map(|(entity, _)| *entity)
+        .collect();
+
+    assert_eq!(index.nearest(center, 10), expected);
+    assert_eq!(index.nearest(center, 1000).len(), 500);
+}
+
+#[test]
+fn moved_and_removed_entities_are_found_where_they_are() {
+    let (mut index, _) = scattered(100);
+    let moved = Entity::from_raw(3);
+    let removed = Entity::from_raw(4);
+
+    index.insert(moved, id(moved), Vec2::new(40., 40.));
+    index.remove(removed);
+
+    assert_eq!(index.len(), 99);
+    assert_eq!(index.position(moved), Some(Vec2::new(40., 40.)));
+    assert_eq!(index.position(removed), None);
+    assert_eq!(
+        index
+            .within_radius(Vec2::new(40., 40.), 0.5)
+            .collect::<Vec<_>>(),
+        vec![moved]
+    );
+    assert!(
+        !index
+            .within_rect(Rect::new(-20., -20., 20., 20.))
+            .any(|entity| entity == moved)
+    );
+}
+
+#[test]
+fn results_do_not_depend_on_the_order_entities_were_added_in() {
+    let (mut index, mut entities) = scattered(300);
+    // Equally distant entities, which `nearest` orders by `UnitId`
+    entities.extend((300..310).map(|raw| (Entity::from_raw(raw), Vec2::new(2., 2.))));
+    for (entity, position) in &entities[300..] {
+        index.insert(*entity, id(*entity), *position);
+    }
+
+    let mut reversed = SpatialIndex::default();
+    for (entity, position) in entities.iter().rev() {
+        // Moved around first, so that buckets are left and joined again
+        reversed.insert(*entity, id(*entity), -*position);
+        reversed.insert(*entity, id(*entity), *position);
+    }
+
+    let center = Vec2::new(1., 1.);
+    assert_eq!(
+        index.within_radius(center, 4.).collect::<Vec<_>>(),
+        reversed.within_radius(center, 4.).collect::<Vec<_>>()
+    );
+    let area = Rect::new(-6., -3., 5., 4.);
+    assert_eq!(
+        index.within_rect(area).collect::<Vec<_>>(),
+        reversed.within_rect(area).collect::<Vec<_>>()
+    );
+    assert_eq!(index.nearest(center, 12), reversed.nearest(center, 12));
+}
+
+#[test]
+fn units_are_indexed_as_they_spawn_and_die() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+    game.advance_ticks(1);
+
+    assert_eq!(
+        game.world().resource::<SpatialIndex>().position(unit),
+        Some(Vec2::new(-2., -2.))
+    );
+
+    game.world_mut().despawn(unit);
+
+    assert_eq!(game.world().resource::<SpatialIndex>().position(unit), None);
+}
+
+#[test]
+fn only_units_that_moved_are_indexed_again() {
+    let mut game = TestGame::new();
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+    game.advance_ticks(2);
+    let unit_id = *game.get::<UnitId>(unit);
+
+    // Misplaced in the index, which a unit standing still must not correct
+    let elsewhere = Vec2::new(5., 5.);
+    game.world_mut()
+        .resource_mut::<SpatialIndex>()
+        .insert(unit, unit_id, elsewhere);
+    game.advance_ticks(3);
+
+    assert_eq!(
+        game.world().resource::<SpatialIndex>().position(unit),
+        Some(elsewhere)
+    );
+
+    game.left_click(unit);
+    game.right_click_ground(Vec2::new(-4., -2.));
+    // Long enough to get there and be indexed where it stopped
+    game.advance_seconds(3.);
+
+    let position = game.position(unit);
+    assert_ne!(position, Vec2::new(-2., -2.));
+    assert_eq!(
+        game.world().resource::<SpatialIndex>().position(unit),
+        Some(position)
+    );
+}
@@ -0,0 +1,214 @@
+//! Drives a headless match from integration tests: units are spawned on the terrain, clicks and
+//! key presses are faked and time advances one simulation tick per update.
+
+// Every test binary only uses part of the harness
+#![allow(dead_code)]
+
+use std::{path::PathBuf, time::Duration};
+
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::FloatOrd,
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerId},
+    },
+    prelude::*,
+    render::camera::{ImageRenderTarget, NormalizedRenderTarget},
+};
+use rts_game_rs::{
+    config::settings::Settings,
+    game_states::GameState,
+    headless::headless_app,
+    match_settings::{MatchSettings, SlotKind},
+    players::{LocalPlayer, Player, PlayerId},
+    save::LoadedSave,
+    simulation::{InterpolatedTranslation, TICKS_PER_SECOND},
+    terrain::Terrain,
+    units::{rendering::UnitAssets, spawn_soldier},
+};
+
+pub struct TestGame {
+    app: App,
+}
+
+impl TestGame {
+    /// A match on the first builtin map played by the local player alone, so that no opponent
+    /// gets in the way and no victory condition can end it.
+    pub fn new() -> Self {
+        let mut settings = MatchSettings::default();
+        for slot in settings.slots.iter_mut().skip(1) {
+            slot.kind = SlotKind::Closed;
+        }
+
+        Self::with_settings(settings)
+    }
+
+    pub fn with_settings(settings: MatchSettings) -> Self {
+        Self::start(settings, None)
+    }
+
+    /// A match picked up from the save at `path`, which was made with `settings`.
+    pub fn load(settings: MatchSettings, path: PathBuf) -> Self {
+        Self::start(settings, Some(LoadedSave(path)))
+    }
+
+    fn start(settings: MatchSettings, save: Option<LoadedSave>) -> Self {
+        let mut app = headless_app();
+        // Bindings saved on the machine running the tests must not change their outcome
+        app.insert_resource(Settings::default())
+            .insert_resource(settings);
+        if let Some(save) = save {
+            app.insert_resource(save);
+        }
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+
+        // Enters the match and runs its first tick, which numbers the starting units
+        app.update();
+
+        Self { app }
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    pub fn advance_ticks(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.app.update();
+        }
+    }
+
+    pub fn advance_seconds(&mut self, seconds: f32) {
+        let ticks = (seconds * TICKS_PER_SECOND as f32).ceil();
+        self.advance_ticks(ticks as u32);
+    }
+
+    pub fn local_player(&self) -> PlayerId {
+        self.world().resource::<LocalPlayer>().0
+    }
+
+    /// Spawns a soldier and runs a tick, after which it can be clicked and commanded.
+    pub fn spawn_soldier(&mut self, owner: PlayerId, position: Vec2) -> Entity {
+        let unit = self
+            .world_mut()
+            .run_system_once(
+                move |mut commands: Commands,
+                      mut assets: UnitAssets,
+                      players_query: Query<&Player>| {
+                    let player = players_query
+                        .iter()
+                        .find(|player| player.id == owner)
+                        .expect("the owner should take part in the match");
+                    spawn_soldier(&mut commands, &mut assets, player, position)
+                },
+            )
+            .expect("spawning a soldier should not fail");
+        self.advance_ticks(1);
+
+        unit
+    }
+
+    pub fn terrain(&mut self) -> Entity {
+        self.world_mut()
+            .query_filtered::<Entity, With<Terrain>>()
+            .single(self.world())
+            .expect("the match should have a terrain")
+    }
+
+    /// Clicks `target` as the picking backend would, `point` being where the ray hit it.
+    pub fn click(&mut self, target: Entity, button: PointerButton, point: Vec3) {
+        let click = Pointer::<Click> {
+            target,
+            pointer_id: PointerId::Mouse,
+            // Nothing reads where the pointer was on screen
+            pointer_location: Location {
+                target: NormalizedRenderTarget::Image(ImageRenderTarget {
+                    handle: Handle::default(),
+                    scale_factor: FloatOrd(1.0),
+                }),
+                position: Vec2::ZERO,
+            },
+            event: Click {
+                button,
+                hit: HitData {
+                    camera: Entity::PLACEHOLDER,
+                    depth: 0.0,
+                    position: Some(point),
+                    normal: Some(Vec3::Y),
+                },
+                duration: Duration::from_millis(100),
+            },
+        };
+
+        let world = self.world_mut();
+        world.trigger_targets(click, target);
+        world.flush();
+    }
+
+    pub fn left_click(&mut self, target: Entity) {
+        let position = self.position(target);
+        self.click(
+            target,
+            PointerButton::Primary,
+            Vec3::new(position.x, 0., position.y),
+        );
+    }
+
+    /// Right clicks the terrain at `point`.
+    pub fn right_click_ground(&mut self, point: Vec2) {
+        let terrain = self.terrain();
+        self.click(
+            terrain,
+            PointerButton::Secondary,
+            Vec3::new(point.x, 0., point.y),
+        );
+    }
+
+    /// Left clicks the terrain at `point`.
+    pub fn left_click_ground(&mut self, point: Vec2) {
+        let terrain = self.terrain();
+        self.click(
+            terrain,
+            PointerButton::Primary,
+            Vec3::new(point.x, 0., point.y),
+        );
+    }
+
+    /// Holds `key` down until [`Self::release_key`], actions bound to it fire on the next update.
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(key);
+    }
+
+    pub fn release_key(&mut self, key: KeyCode) {
+        self.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release(key);
+    }
+
+    pub fn has<C: Component>(&self, entity: Entity) -> bool {
+        self.world().entity(entity).contains::<C>()
+    }
+
+    pub fn get<C: Component>(&self, entity: Entity) -> &C {
+        self.world()
+            .get::<C>(entity)
+            .unwrap_or_else(|| panic!("{entity} should have a {}", std::any::type_name::<C>()))
+    }
+
+    /// Where the simulation put the unit on the ground, the rendered transform lags behind.
+    pub fn position(&self, entity: Entity) -> Vec2 {
+        match self.world().get::<InterpolatedTranslation>(entity) {
+            Some(interpolated) => interpolated.current.xz(),
+            None => self.get::<Transform>(entity).translation.xz(),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+mod support;
+
+use bevy::{ecs::query::QueryFilter, prelude::*};
+use rts_game_rs::{
+    buildings::Building,
+    config::victory::VictoryConfig,
+    match_settings::{MatchSettings, SlotKind, VictoryCondition},
+    players::{Owner, PlayerId},
+    units::{Unit, health::Damage},
+    victory::{MatchProgress, MatchResult},
+};
+use support::TestGame;
+
+/// Two human players, so that nobody gives orders of their own.
+fn duel(condition: VictoryCondition) -> MatchSettings {
+    let mut settings = MatchSettings::default();
+    settings.slots[1].kind = SlotKind::Human;
+    settings.victory_condition = condition;
+    settings
+}
+
+/// Deals enough damage to bring down everything `player` owns matching `F`.
+fn destroy<F: QueryFilter>(game: &mut TestGame, player: PlayerId) {
+    let targets: Vec<Entity> = game
+        .world_mut()
+        .query_filtered::<(Entity, &Owner), F>()
+        .iter(game.world())
+        .filter(|(_, owner)| owner.0 == player)
+        .map(|(entity, _)| entity)
+        .collect();
+    for target in targets {
+        game.world_mut().send_event(Damage {
+            target,
+            source: None,
+            amount: f32::MAX,
+        });
+    }
+    game.advance_ticks(1);
+}
+
+fn winning_team(game: &TestGame) -> Option<Option<u8>> {
+    game.world()
+        .get_resource::<MatchResult>()
+        .map(|result| result.winning_team)
+}
+
+#[test]
+fn destroying_every_building_of_a_team_wins() {
+    let mut game = TestGame::with_settings(duel(VictoryCondition::DestroyAllBuildings));
+
+    // Units can be trained again from a base left standing
+    destroy::<With<Unit>>(&mut game, PlayerId(1));
+    assert_eq!(winning_team(&game), None);
+
+    destroy::<With<Building>>(&mut game, PlayerId(1));
+    assert_eq!(winning_team(&game), Some(Some(1)));
+}
+
+#[test]
+fn losing_every_unit_still_loses_other_matches() {
+    let mut game = TestGame::with_settings(duel(VictoryCondition::EliminateAllUnits));
+
+    destroy::<With<Unit>>(&mut game, PlayerId(1));
+
+    assert_eq!(winning_team(&game), Some(Some(1)));
+}
+
+#[test]
+fn teams_can_be_numbered_from_zero() {
+    let mut settings = duel(VictoryCondition::HoldPoint);
+    settings.slots[0].team = 0;
+    let mut game = TestGame::with_settings(settings);
+    let point = game.world().resource::<VictoryConfig>().hold_point;
+    game.spawn_soldier(PlayerId(0), point);
+
+    game.advance_seconds(1.);
+
+    let held = game.world().resource::<MatchProgress>().held_seconds[&0];
+    assert!(held > 0.);
+}
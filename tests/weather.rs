@@ -0,0 +1,55 @@
+mod support;
+
+use bevy::prelude::*;
+use rts_game_rs::{
+    match_settings::{MatchSettings, SlotKind},
+    units::MoveTo,
+    weather::{Weather, WeatherSchedule, WeatherState, WeatherTrigger},
+};
+use support::TestGame;
+
+fn game_with_weather(triggers: Vec<WeatherTrigger>) -> TestGame {
+    let mut settings = MatchSettings::default();
+    for slot in settings.slots.iter_mut().skip(1) {
+        slot.kind = SlotKind::Closed;
+    }
+    settings.map.weather = WeatherSchedule::Triggers(triggers);
+
+    TestGame::with_settings(settings)
+}
+
+#[test]
+fn map_triggers_change_the_weather() {
+    let mut game = game_with_weather(vec![WeatherTrigger {
+        at_seconds: 1.,
+        weather: Weather::Rain,
+    }]);
+    assert_eq!(
+        game.world().resource::<WeatherState>().current,
+        Weather::Clear
+    );
+
+    game.advance_seconds(1.5);
+
+    assert_eq!(
+        game.world().resource::<WeatherState>().current,
+        Weather::Rain
+    );
+}
+
+#[test]
+fn units_are_slower_in_snow() {
+    let mut game = game_with_weather(vec![WeatherTrigger {
+        at_seconds: 0.,
+        weather: Weather::Snow,
+    }]);
+    let unit = game.spawn_soldier(game.local_player(), Vec2::new(-2., -2.));
+
+    game.left_click(unit);
+    game.right_click_ground(Vec2::new(1., -2.));
+    // Long enough to cross the three units in clear weather
+    game.advance_seconds(3.5);
+
+    assert!(game.has::<MoveTo>(unit));
+    assert!(game.position(unit).x < 1.);
+}